@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day05::Day05};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day05::parse(input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day10::Day10};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day10::parse(input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day13::Day13};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day13::parse(input);
+});
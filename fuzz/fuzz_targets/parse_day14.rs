@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day14::Day14};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day14::parse(input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day18::Day18};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day18::parse(input);
+});
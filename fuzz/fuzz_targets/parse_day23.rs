@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day23::Day23};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day23::parse(input);
+});
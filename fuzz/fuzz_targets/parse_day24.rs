@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc2023::{day::Day, day24::Day24};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day24::parse(input);
+});
@@ -1,15 +1,148 @@
+use num::BigInt;
 use reqwest::{self, header::COOKIE};
 use std::{
     error,
-    fmt::{self, Display, Formatter},
+    fmt::{self, Debug, Display, Formatter},
     fs, io,
+    num::{ParseFloatError, ParseIntError},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
     time::Instant,
 };
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError(String);
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl From<ParseIntError> for ParseError {
+    fn from(err: ParseIntError) -> Self {
+        Self::new(format!("invalid integer ({})", err))
+    }
+}
+
+impl From<ParseFloatError> for ParseError {
+    fn from(err: ParseFloatError) -> Self {
+        Self::new(format!("invalid number ({})", err))
+    }
+}
+
+pub trait Expected<T> {
+    fn expected(self, what: &str) -> Result<T, ParseError>;
+}
+
+impl<T> Expected<T> for Option<T> {
+    fn expected(self, what: &str) -> Result<T, ParseError> {
+        self.ok_or_else(|| ParseError::new(format!("expected {}", what)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError(String);
+
+impl SolveError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl error::Error for SolveError {}
+
+// for checked arithmetic, where None means the result didn't fit
+pub trait Checked<T> {
+    fn checked(self, what: &str) -> Result<T, SolveError>;
+}
+
+impl<T> Checked<T> for Option<T> {
+    fn checked(self, what: &str) -> Result<T, SolveError> {
+        self.ok_or_else(|| SolveError::new(format!("{} overflowed", what)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Big(BigInt),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Big(n) => write!(f, "{}", n),
+            Self::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($ty:ty),+) => {$(
+        impl From<$ty> for Answer {
+            fn from(n: $ty) -> Self {
+                Self::Int(n as i128)
+            }
+        }
+    )+};
+}
+
+answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128);
+
+impl From<BigInt> for Answer {
+    fn from(n: BigInt) -> Self {
+        Self::Big(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+// mostly for tests, so answers can be checked against the puzzle text
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        match self {
+            Self::Int(n) => other.parse() == Ok(*n),
+            Self::Big(n) => other.parse().as_ref() == Ok(n),
+            Self::Text(s) => s == other,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     MissingCookie(io::Error),
     DownloadingInput(reqwest::Error),
+    ParsingInput(usize, ParseError),
+    Solving(usize, SolveError),
+    Visualizing(io::Error),
+    Interacting(io::Error),
 }
 
 impl Display for Error {
@@ -23,20 +156,95 @@ impl Display for Error {
                 err
             ),
             Self::DownloadingInput(err) => write!(f, "Couldn't download input... [{}]", err),
+            Self::ParsingInput(day, err) => {
+                write!(f, "Couldn't parse input for day {} [{}]", day, err)
+            }
+            Self::Solving(day, err) => write!(f, "Couldn't solve day {} [{}]", day, err),
+            Self::Visualizing(err) => write!(f, "Couldn't write visualization [{}]", err),
+            Self::Interacting(err) => write!(f, "Couldn't run the repl [{}]", err),
         }
     }
 }
 
 impl error::Error for Error {}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+// which parts to run, and whether to run both at once on separate threads
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Run {
+    pub only: Option<Part>,
+    pub concurrent: bool,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::One => write!(f, "part1"),
+            Self::Two => write!(f, "part2"),
+        }
+    }
+}
+
+// 0 until `--threads` sets it
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn parse_threads(spec: &str) -> Result<usize, String> {
+    spec.parse()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("Expected a positive number of threads, not \"{}\"", spec))
+}
+
+pub fn set_threads(n: usize) {
+    THREADS.store(n, Ordering::Relaxed);
+}
+
+// how many threads a solver may split its own work over: `--threads`, else the
+// AOC_THREADS environment variable, else the available parallelism
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => std::env::var("AOC_THREADS")
+            .ok()
+            .and_then(|spec| parse_threads(&spec).ok())
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, usize::from)),
+        n => n,
+    }
+}
+
+pub fn timed<T, F: FnOnce() -> T>(f: F) -> (T, f32) {
+    let start = Instant::now();
+    let result = f();
+    (result, 1000.0 * start.elapsed().as_secs_f32())
+}
+
 pub trait Day<'a> {
     const DAY: usize;
-    type Input;
-    type ProcessedInput;
+    // (usage, description) of the commands handled by `query`
+    const QUERIES: &'static [(&'static str, &'static str)] = &[];
+    type Input: Debug + Sync;
+    // work both parts need, done once before either runs
+    type Shared: Sync;
 
-    fn parse(input: &'a str) -> Self::Input;
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String);
-    fn solve_part2(input: Self::ProcessedInput) -> String;
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError>;
+    fn prepare(input: &Self::Input) -> Result<Self::Shared, SolveError>;
+    fn part1(input: &Self::Input, shared: &Self::Shared) -> Result<Answer, SolveError>;
+    fn part2(input: &Self::Input, shared: &Self::Shared) -> Result<Answer, SolveError>;
+
+    fn query(_input: &Self::Input, command: &str, _args: &[&str]) -> Result<String, String> {
+        Err(format!("Unknown command \"{}\"", command))
+    }
+
+    fn solve(part: Part, input: &Self::Input, shared: &Self::Shared) -> Result<Answer, SolveError> {
+        match part {
+            Part::One => Self::part1(input, shared),
+            Part::Two => Self::part2(input, shared),
+        }
+    }
 
     fn get_input() -> Result<String, Error> {
         let input_path = format!("cache/day{}.in", Self::DAY);
@@ -62,32 +270,47 @@ pub trait Day<'a> {
         })
     }
 
-    fn solve_and_print(input: &'a str) {
+    fn solve_and_print(input: &'a str, run: Run) -> Result<(), Error> {
         println!();
         println!("day{:02}:", Self::DAY);
+        let failed = |err| Error::Solving(Self::DAY, err);
+
+        let (input, elapsed) = timed(|| Self::parse(input));
+        let input = input.map_err(|err| Error::ParsingInput(Self::DAY, err))?;
+        println!("  parsing: ... (elapsed {}ms)", elapsed);
 
-        let start_time = Instant::now();
-        let input = Self::parse(input);
-        let parsed_time = Instant::now();
-        println!(
-            "  parsing: ... (elapsed {}ms)",
-            1000.0 * (parsed_time - start_time).as_secs_f32()
-        );
-
-        let (processed_input, part1_answer) = Self::solve_part1(input);
-        let part1_time = Instant::now();
-        println!(
-            "  part1: {} (elapsed {}ms)",
-            part1_answer,
-            1000.0 * (part1_time - parsed_time).as_secs_f32()
-        );
-
-        let part2_answer = Self::solve_part2(processed_input);
-        let part2_time = Instant::now();
-        println!(
-            "  part2: {} (elapsed {}ms)",
-            part2_answer,
-            1000.0 * (part2_time - part1_time).as_secs_f32()
-        )
+        let (shared, elapsed) = timed(|| Self::prepare(&input));
+        let shared = shared.map_err(failed)?;
+        println!("  prepare: ... (elapsed {}ms)", elapsed);
+
+        let parts = match run.only {
+            Some(part) => vec![part],
+            None => vec![Part::One, Part::Two],
+        };
+        let solve = |part| timed(|| Self::solve(part, &input, &shared));
+        let print = |part, (answer, elapsed): (Result<Answer, SolveError>, f32)| {
+            println!(
+                "  {}: {} (elapsed {}ms)",
+                part,
+                answer.map_err(failed)?,
+                elapsed
+            );
+            Ok(())
+        };
+        match run.concurrent {
+            true => std::thread::scope(|scope| {
+                let threads = parts
+                    .iter()
+                    .map(|&part| (part, scope.spawn(move || solve(part))))
+                    .collect::<Vec<_>>();
+                threads
+                    .into_iter()
+                    .try_for_each(|(part, thread)| print(part, thread.join().unwrap()))
+            }),
+            false => parts
+                .into_iter()
+                .try_for_each(|part| print(part, solve(part))),
+        }?;
+        Ok(())
     }
 }
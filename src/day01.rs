@@ -1,4 +1,4 @@
-use crate::day::Day;
+use crate::day::{Answer, Day, ParseError, SolveError};
 
 const DIGITS: [(&str, u32); 9] = [
     ("one", 1),
@@ -41,26 +41,30 @@ impl<'a> Day<'a> for Day01 {
     const DAY: usize = 1;
 
     type Input = Vec<&'a str>;
-    type ProcessedInput = Vec<&'a str>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        input.trim().lines().collect()
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        Ok(input.trim().lines().collect())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = input
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(input
             .iter()
             .filter_map(|s| first_last_digits(s, false))
-            .sum::<u32>();
-        (input, ans.to_string())
+            .sum::<u32>()
+            .into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        input
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(input
             .iter()
             .filter_map(|s| first_last_digits(s, true))
             .sum::<u32>()
-            .to_string()
+            .into())
     }
 }
 
@@ -88,13 +92,12 @@ mod test_day01 {
 
     #[test]
     fn test_day01_examples() {
-        let input = Day01::parse(EXAMPLE1);
-        let (_, part1) = Day01::solve_part1(input);
+        let input = Day01::parse(EXAMPLE1).unwrap();
+        let part1 = Day01::part1(&input, &()).unwrap();
         assert_eq!(part1, "142");
 
-        let input = Day01::parse(EXAMPLE2);
-        let (input, _) = Day01::solve_part1(input);
-        let part2 = Day01::solve_part2(input);
+        let input = Day01::parse(EXAMPLE2).unwrap();
+        let part2 = Day01::part2(&input, &()).unwrap();
         assert_eq!(part2, "281");
     }
 }
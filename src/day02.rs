@@ -1,4 +1,4 @@
-use crate::day::Day;
+use crate::day::{Answer, Day, Expected, ParseError, SolveError};
 
 const COLORS: [&str; 3] = ["red", "green", "blue"];
 
@@ -18,51 +18,59 @@ impl<'a> Day<'a> for Day02 {
     const DAY: usize = 2;
 
     type Input = Vec<(u32, Vec<[u32; 3]>)>;
-    type ProcessedInput = Vec<(u32, Vec<[u32; 3]>)>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .trim()
             .lines()
             .map(|line| {
-                let (idx, content) = line.split_once(':').unwrap();
-                let game_idx = idx.split(' ').last().unwrap().parse().unwrap();
+                let (idx, content) = line.split_once(':').expected("':' after game id")?;
+                let game_idx = idx.split(' ').next_back().expected("game id")?.parse()?;
                 let reveals = content
                     .split(';')
                     .map(|reveal| {
                         let mut shown = [0; 3];
-                        reveal.split(',').for_each(|amount| {
-                            let (number, color) = amount.trim().split_once(' ').unwrap();
-                            let color_idx = COLORS.iter().position(|&c| c == color).unwrap();
-                            shown[color_idx] = number.parse().unwrap();
-                        });
-                        shown
+                        for amount in reveal.split(',') {
+                            let (number, color) =
+                                amount.trim().split_once(' ').expected("count and color")?;
+                            let color_idx = COLORS
+                                .iter()
+                                .position(|&c| c == color)
+                                .expected("a color")?;
+                            shown[color_idx] = number.parse()?;
+                        }
+                        Ok(shown)
                     })
-                    .collect();
-                (game_idx, reveals)
+                    .collect::<Result<_, ParseError>>()?;
+                Ok((game_idx, reveals))
             })
             .collect()
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let amounts = [12, 13, 14];
-        let ans = input
+        Ok(input
             .iter()
             .filter(|&(_id, reveals)| reveals.iter().all(|&reveal| three_le(reveal, amounts)))
             .map(|(id, _reveals)| id)
-            .sum::<u32>();
-        (input, ans.to_string())
+            .sum::<u32>()
+            .into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        input
-            .into_iter()
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
             .map(|(_id, reveals)| {
-                let max = reveals.into_iter().fold([0; 3], three_max);
+                let max = reveals.iter().copied().fold([0; 3], three_max);
                 max[0] * max[1] * max[2]
             })
             .sum::<u32>()
-            .to_string()
+            .into())
     }
 }
 
@@ -81,9 +89,9 @@ mod test_day02 {
 
     #[test]
     fn test_day02_examples() {
-        let input = Day02::parse(EXAMPLE);
-        let (input, part1) = Day02::solve_part1(input);
-        let part2 = Day02::solve_part2(input);
+        let input = Day02::parse(EXAMPLE).unwrap();
+        let part1 = Day02::part1(&input, &()).unwrap();
+        let part2 = Day02::part2(&input, &()).unwrap();
         assert_eq!(part1, "8");
         assert_eq!(part2, "2286");
     }
@@ -4,8 +4,8 @@ use std::{
 };
 
 use crate::{
-    util::{self, LineGrid},
-    Day,
+    day::{Answer, Checked, Day, ParseError, SolveError},
+    util::{math, LineGrid, Point},
 };
 
 pub struct Day03;
@@ -14,18 +14,19 @@ impl<'a> Day<'a> for Day03 {
     const DAY: usize = 3;
 
     type Input = LineGrid<'a>;
-    type ProcessedInput = HashMap<(usize, usize, char), Vec<u32>>;
+    type Shared = HashMap<(usize, usize, char), Vec<u32>>;
 
-    fn parse(input: &'a str) -> Self::Input {
-        LineGrid::new(input)
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        LineGrid::parse(input)
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
+    // the numbers next to each symbol
+    fn prepare(input: &Self::Input) -> Result<Self::Shared, SolveError> {
         let mut symbols = HashMap::new();
         for (i, j, c) in input.iter() {
             if !c.is_ascii_digit() && c != '.' {
-                util::grid_neighbours((i, j)).for_each(|(ni, nj)| {
-                    symbols.insert((ni, nj), (i, j, c));
+                input.neighbours8(Point::from((i, j))).for_each(|p| {
+                    symbols.insert(p, (i, j, c));
                 })
             }
         }
@@ -40,10 +41,10 @@ impl<'a> Day<'a> for Day03 {
                 if c.is_ascii_digit() {
                     start = start.or(Some(j));
                 } else if let Some(k) = start {
-                    let number = input.section(i, k..j).parse().unwrap();
+                    let number = input.section(i, k..j).parse().ok().checked("part number")?;
                     let mut seen = HashSet::new();
                     for l in k..j {
-                        if let Some(s) = symbols.get(&(i as i32, l as i32)).copied() {
+                        if let Some(s) = symbols.get(&Point::from((i, l))).copied() {
                             seen.insert(s);
                         }
                     }
@@ -53,17 +54,22 @@ impl<'a> Day<'a> for Day03 {
                 }
             }
         }
-        let ans = numbers.values().flat_map(|ns| ns.iter()).sum::<u32>();
-        (numbers, ans.to_string())
+        Ok(numbers)
     }
 
-    fn solve_part2(numbers: Self::ProcessedInput) -> String {
-        numbers
-            .into_iter()
+    fn part1(_: &Self::Input, numbers: &Self::Shared) -> Result<Answer, SolveError> {
+        let sum = math::checked_sum(numbers.values().flatten().copied());
+        Ok(sum.checked("sum of part numbers")?.into())
+    }
+
+    fn part2(_: &Self::Input, numbers: &Self::Shared) -> Result<Answer, SolveError> {
+        let ratios = numbers
+            .iter()
             .filter(|((_i, _j, c), ns)| *c == '*' && ns.len() == 2)
-            .map(|(_k, ns)| ns[0] * ns[1])
-            .sum::<u32>()
-            .to_string()
+            .map(|(_k, ns)| ns[0].checked_mul(ns[1]))
+            .collect::<Option<Vec<_>>>();
+        let sum = ratios.and_then(math::checked_sum);
+        Ok(sum.checked("sum of gear ratios")?.into())
     }
 }
 
@@ -87,10 +93,14 @@ mod test_day03 {
 
     #[test]
     fn test_day03_examples() {
-        let input = Day03::parse(EXAMPLE);
-        let (input, part1) = Day03::solve_part1(input);
-        let part2 = Day03::solve_part2(input);
+        let input = Day03::parse(EXAMPLE).unwrap();
+        let shared = Day03::prepare(&input).unwrap();
+        let part1 = Day03::part1(&input, &shared).unwrap();
+        let part2 = Day03::part2(&input, &shared).unwrap();
         assert_eq!(part1, "4361");
         assert_eq!(part2, "467835");
+
+        let err = Day03::prepare(&LineGrid::new("12345678901*")).unwrap_err();
+        assert_eq!(err.to_string(), "part number overflowed");
     }
 }
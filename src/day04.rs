@@ -1,4 +1,9 @@
-use crate::day::Day;
+use std::num::ParseIntError;
+
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    util::math,
+};
 
 pub struct Day04;
 
@@ -6,43 +11,57 @@ impl<'a> Day<'a> for Day04 {
     const DAY: usize = 4;
 
     type Input = Vec<(Vec<u32>, Vec<u32>)>;
-    type ProcessedInput = Vec<usize>;
+    type Shared = Vec<usize>;
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .trim()
             .lines()
             .map(|line| {
-                let (_card, content) = line.split_once(':').unwrap();
-                let (win, have) = content.trim().split_once('|').unwrap();
-                let get_nums = |s: &str| s.split_whitespace().map(|n| n.parse().unwrap()).collect();
-                (get_nums(win), get_nums(have))
+                let (_card, content) = line.split_once(':').expected("':' after card id")?;
+                let (win, have) = content.trim().split_once('|').expected("'|' in card")?;
+                let get_nums = |s: &str| {
+                    s.split_whitespace()
+                        .map(str::parse)
+                        .collect::<Result<_, ParseIntError>>()
+                };
+                Ok((get_nums(win)?, get_nums(have)?))
             })
             .collect()
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let wins = input
-            .into_iter()
+    fn prepare(input: &Self::Input) -> Result<Self::Shared, SolveError> {
+        Ok(input
+            .iter()
             .map(|(win, have)| have.iter().filter(|n| win.contains(n)).count())
-            .collect::<Vec<_>>();
-        let ans = wins
+            .collect())
+    }
+
+    fn part1(_: &Self::Input, wins: &Self::Shared) -> Result<Answer, SolveError> {
+        let points = wins
             .iter()
             .filter(|&&n| n > 0)
-            .map(|n| 1 << (n - 1))
-            .sum::<usize>();
-        (wins, ans.to_string())
+            .map(|&n| 1usize.checked_shl(n as u32 - 1))
+            .collect::<Option<Vec<_>>>();
+        Ok(points.and_then(math::checked_sum).checked("points")?.into())
     }
 
-    fn solve_part2(wins: Self::ProcessedInput) -> String {
-        let mut copies = vec![1; wins.len()];
+    fn part2(_: &Self::Input, wins: &Self::Shared) -> Result<Answer, SolveError> {
+        let mut copies = vec![1usize; wins.len()];
         let mut done = vec![0; wins.len()];
         while let Some(card) = (0..copies.len()).find(|&i| copies[i] > 0) {
-            (card + 1..=card + wins[card]).for_each(|c| copies[c] += copies[card]);
+            // cards never win copies of cards past the end of the table
+            for c in card + 1..=(card + wins[card]).min(wins.len() - 1) {
+                copies[c] = copies[c]
+                    .checked_add(copies[card])
+                    .checked("copies of a card")?;
+            }
             done[card] += copies[card];
             copies[card] = 0;
         }
-        done.into_iter().sum::<usize>().to_string()
+        Ok(math::checked_sum(done)
+            .checked("total scratchcards")?
+            .into())
     }
 }
 
@@ -62,9 +81,10 @@ mod test_day04 {
 
     #[test]
     fn test_day04_examples() {
-        let input = Day04::parse(EXAMPLE);
-        let (input, part1) = Day04::solve_part1(input);
-        let part2 = Day04::solve_part2(input);
+        let input = Day04::parse(EXAMPLE).unwrap();
+        let shared = Day04::prepare(&input).unwrap();
+        let part1 = Day04::part1(&input, &shared).unwrap();
+        let part2 = Day04::part2(&input, &shared).unwrap();
         assert_eq!(part1, "13");
         assert_eq!(part2, "30");
     }
@@ -1,96 +1,93 @@
-use crate::day::Day;
-
-struct SeedRange {
-    ranges: Vec<(i64, i64)>,
-}
-
-impl SeedRange {
-    fn min_loc(mut seeds: Vec<SeedRange>, maps: &[Vec<(i64, i64, i64)>]) -> i64 {
-        seeds
-            .iter_mut()
-            .for_each(|s| maps.iter().for_each(|map| s.transform(map)));
-        seeds
-            .iter()
-            .flat_map(|s| s.ranges.iter().map(|r| r.0))
-            .min()
-            .unwrap()
-    }
-
-    fn transform(&mut self, map: &[(i64, i64, i64)]) {
-        let mut new_ranges = Vec::new();
-        for (dest, source, len) in map.iter().copied() {
-            let mut i = 0;
-            while i < self.ranges.len() {
-                let (start, end) = self.ranges[i];
-                let new_start = source.max(start) + dest - source;
-                let new_end = (source + len).min(end) + dest - source;
-                if new_end > new_start {
-                    self.ranges.swap_remove(i);
-                    new_ranges.push((new_start, new_end));
-                    if source > start {
-                        self.ranges.push((start, source));
-                    }
-                    if end > source + len {
-                        self.ranges.push((source + len, end));
-                    }
-                } else {
-                    i += 1;
-                }
-            }
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    util::{
+        interval::{Interval, IntervalSet},
+        parse,
+    },
+};
+
+fn transform(
+    seeds: IntervalSet<i64>,
+    map: &[(i64, i64, i64)],
+) -> Result<IntervalSet<i64>, SolveError> {
+    let (mut unmapped, mut mapped) = (seeds, IntervalSet::new());
+    for &(dest, source, len) in map.iter() {
+        let end = source.checked_add(len).checked("map range")?;
+        let offset = dest.checked_sub(source).checked("map range")?;
+        let sources = IntervalSet::from(Interval::new(source, end));
+        for i in unmapped.intersection(&sources).iter() {
+            mapped.insert(i.shift(offset).checked("mapped range")?);
         }
-        self.ranges.extend(new_ranges);
+        unmapped = unmapped.difference(&sources);
     }
+    Ok(mapped.union(&unmapped))
+}
+
+fn min_loc(seeds: IntervalSet<i64>, maps: &[Vec<(i64, i64, i64)>]) -> Result<i64, SolveError> {
+    maps.iter()
+        .try_fold(seeds, |s, map| transform(s, map))?
+        .min()
+        .ok_or_else(|| SolveError::new("no seeds to plant"))
 }
 
 pub struct Day05;
 
 impl<'a> Day<'a> for Day05 {
     const DAY: usize = 5;
+    const QUERIES: &'static [(&'static str, &'static str)] =
+        &[("location <seed>", "location a seed is planted at")];
 
     type Input = (Vec<i64>, Vec<Vec<(i64, i64, i64)>>);
-    type ProcessedInput = (Vec<i64>, Vec<Vec<(i64, i64, i64)>>);
-
-    fn parse(input: &'a str) -> Self::Input {
-        let mut pars = input.split("\n\n");
-        let seeds = pars.next().unwrap();
-        let (_, seeds) = seeds.split_once(':').unwrap();
-        let seeds = seeds
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
+    type Shared = ();
+
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        let mut pars = parse::paragraphs(input);
+        let (_, seeds) = parse::key_values(pars.next().expected("seeds")?)?;
+        let seeds = seeds.map(str::parse).collect::<Result<_, _>>()?;
         let maps = pars
             .map(|par| {
                 par.lines()
                     .skip(1)
-                    .map(|line| {
-                        let mut ns = line.split_whitespace();
-                        let mut eat = || ns.next().unwrap().parse().unwrap();
-                        (eat(), eat(), eat())
-                    })
+                    .map(|line| parse::ints_array(line).map(|[d, s, n]| (d, s, n)))
                     .collect()
             })
-            .collect();
-        (seeds, maps)
+            .collect::<Result<_, ParseError>>()?;
+        Ok((seeds, maps))
     }
 
-    fn solve_part1((seeds, maps): Self::Input) -> (Self::ProcessedInput, String) {
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1((seeds, maps): &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let seeds = seeds.iter().map(|&s| Interval::single(s).checked("seed"));
+        Ok(min_loc(seeds.collect::<Result<_, _>>()?, maps)?.into())
+    }
+
+    fn part2((seeds, maps): &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let seed_ranges = seeds
-            .iter()
-            .map(|&s| SeedRange {
-                ranges: vec![(s, s + 1)],
+            .chunks_exact(2)
+            .map(|s| {
+                Ok(Interval::new(
+                    s[0],
+                    s[0].checked_add(s[1]).checked("seed range")?,
+                ))
             })
-            .collect::<Vec<_>>();
-        let ans = SeedRange::min_loc(seed_ranges, &maps);
-        ((seeds, maps), ans.to_string())
+            .collect::<Result<_, SolveError>>()?;
+        Ok(min_loc(seed_ranges, maps)?.into())
     }
 
-    fn solve_part2((seeds, maps): Self::ProcessedInput) -> String {
-        let seed_ranges = (0..seeds.len() / 2)
-            .map(|i| SeedRange {
-                ranges: vec![(seeds[2 * i], seeds[2 * i] + seeds[2 * i + 1])],
-            })
-            .collect::<Vec<_>>();
-        SeedRange::min_loc(seed_ranges, &maps).to_string()
+    fn query((_, maps): &Self::Input, command: &str, args: &[&str]) -> Result<String, String> {
+        match (command, args) {
+            ("location", [seed]) => {
+                let seed = seed.parse::<i64>().map_err(|err| err.to_string())?;
+                let seed = Interval::single(seed).checked("seed");
+                let loc = seed.and_then(|seed| min_loc(seed.into(), maps));
+                loc.map(|loc| loc.to_string())
+                    .map_err(|err| err.to_string())
+            }
+            _ => Err(format!("Unknown command \"{}\"", command)),
+        }
     }
 }
 
@@ -137,10 +134,20 @@ mod test_day05 {
 
     #[test]
     fn test_day05_examples() {
-        let input = Day05::parse(EXAMPLE);
-        let (input, part1) = Day05::solve_part1(input);
-        let part2 = Day05::solve_part2(input);
+        let input = Day05::parse(EXAMPLE).unwrap();
+        let part1 = Day05::part1(&input, &()).unwrap();
+        let part2 = Day05::part2(&input, &()).unwrap();
         assert_eq!(part1, "35");
         assert_eq!(part2, "46");
+
+        let (_, maps) = input;
+        assert!(Day05::part2(&(vec![79], maps), &()).is_err());
+
+        let input = format!("seeds: {} 2\n\nseed-to-soil map:\n0 1 1\n", i64::MAX);
+        let err = Day05::part2(&Day05::parse(&input).unwrap(), &()).unwrap_err();
+        assert_eq!(err.to_string(), "seed range overflowed");
+        let input = format!("seeds: 5\n\nseed-to-soil map:\n{} 0 10\n", i64::MAX);
+        let err = Day05::part1(&Day05::parse(&input).unwrap(), &()).unwrap_err();
+        assert_eq!(err.to_string(), "mapped range overflowed");
     }
 }
@@ -1,4 +1,7 @@
-use crate::day::Day;
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    util::{math, parse},
+};
 
 const EPSILON: f64 = 0.00001;
 
@@ -8,14 +11,18 @@ fn harsh_floor(t: f64) -> i64 {
     f as i64 - d
 }
 
-fn count_ways(time: i64, distance: i64) -> i64 {
-    let disc = time * time - 4 * distance;
+fn count_ways(time: i64, distance: i64) -> Result<i64, SolveError> {
+    let disc = time
+        .checked_mul(time)
+        .zip(distance.checked_mul(4))
+        .and_then(|(t2, d4)| t2.checked_sub(d4))
+        .checked("discriminant")?;
     if disc < 0 {
-        time + 1
+        Ok(0)
     } else {
         let r = (disc as f64).sqrt();
         let (r1, r2) = ((time as f64 - r) / 2.0, (time as f64 + r) / 2.0);
-        harsh_floor(r2).min(time) + harsh_floor(-r1).min(0) + 1
+        Ok((harsh_floor(r2).min(time) + harsh_floor(-r1).min(0) + 1).max(0))
     }
 }
 
@@ -24,39 +31,37 @@ pub struct Day06;
 impl<'a> Day<'a> for Day06 {
     const DAY: usize = 6;
 
-    type Input = (&'a str, &'a str);
-    type ProcessedInput = (&'a str, &'a str);
+    type Input = (Vec<(i64, i64)>, (i64, i64));
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         let mut lines = input.trim().lines();
-        let mut eat = || lines.next().unwrap();
-        (eat(), eat())
+        let mut eat = || -> Result<(Vec<i64>, i64), ParseError> {
+            let (_, nums) = parse::key_values(lines.next().expected("a line")?)?;
+            let nums = nums.collect::<Vec<_>>();
+            let kerned = nums.concat().parse()?;
+            let nums = nums.into_iter().map(str::parse).collect::<Result<_, _>>()?;
+            Ok((nums, kerned))
+        };
+        let ((times, time), (distances, distance)) = (eat()?, eat()?);
+        Ok((times.into_iter().zip(distances).collect(), (time, distance)))
     }
 
-    fn solve_part1((times, distances): Self::Input) -> (Self::ProcessedInput, String) {
-        let get_nums = |s: &'a str| {
-            s.split_once(':')
-                .unwrap()
-                .1
-                .split_whitespace()
-                .map(|n| n.parse().unwrap())
-        };
-        let ans = get_nums(times)
-            .zip(get_nums(distances))
-            .map(|(t, d)| count_ways(t, d))
-            .product::<i64>();
-        ((times, distances), ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part2((times, distances): Self::ProcessedInput) -> String {
-        let get_num = |s: &'a str| {
-            s.chars()
-                .filter(char::is_ascii_digit)
-                .collect::<String>()
-                .parse()
-                .unwrap()
-        };
-        count_ways(get_num(times), get_num(distances)).to_string()
+    fn part1((races, _): &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let ways = races
+            .iter()
+            .map(|&(t, d)| count_ways(t, d))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ans = math::checked_product(ways).checked("product of ways")?;
+        Ok(ans.into())
+    }
+
+    fn part2(&(_, (time, distance)): &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(count_ways(time, distance)?.into())
     }
 }
 
@@ -64,6 +69,7 @@ impl<'a> Day<'a> for Day06 {
 mod test_day06 {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc! {"
         Time:      7  15   30
@@ -72,10 +78,24 @@ mod test_day06 {
 
     #[test]
     fn test_day06_examples() {
-        let input = Day06::parse(EXAMPLE);
-        let (input, part1) = Day06::solve_part1(input);
-        let part2 = Day06::solve_part2(input);
+        let input = Day06::parse(EXAMPLE).unwrap();
+        let part1 = Day06::part1(&input, &()).unwrap();
+        let part2 = Day06::part2(&input, &()).unwrap();
         assert_eq!(part1, "288");
         assert_eq!(part2, "71503");
+
+        let err = count_ways(i64::MAX / 2, 1).unwrap_err();
+        assert_eq!(err.to_string(), "discriminant overflowed");
+    }
+
+    fn brute_count_ways(time: i64, distance: i64) -> i64 {
+        (0..=time).filter(|h| h * (time - h) > distance).count() as i64
+    }
+
+    proptest! {
+        #[test]
+        fn test_day06_count_ways(time in 0..1000i64, distance in 0..300000i64) {
+            prop_assert_eq!(count_ways(time, distance), Ok(brute_count_ways(time, distance)));
+        }
     }
 }
@@ -1,17 +1,18 @@
 use std::cmp::Ordering;
 
-use crate::day::Day;
+use crate::day::{Answer, Day, Expected, ParseError, SolveError};
 
-fn hand_values(hand: &str) -> [usize; 5] {
+fn hand_values(hand: &str) -> Result<[usize; 5], ParseError> {
     let val = |c: u8| {
         "23456789TJQKA"
-            .as_bytes()
-            .iter()
-            .position(|&b| b == c)
-            .unwrap()
+            .bytes()
+            .position(|b| b == c)
+            .expected("a card")
     };
-    let bs = hand.as_bytes();
-    [val(bs[0]), val(bs[1]), val(bs[2]), val(bs[3]), val(bs[4])]
+    match hand.as_bytes() {
+        &[b0, b1, b2, b3, b4] => Ok([val(b0)?, val(b1)?, val(b2)?, val(b3)?, val(b4)?]),
+        _ => Err(ParseError::new("expected five cards")),
+    }
 }
 
 fn hand_strength(values: [usize; 5], with_jokers: bool) -> usize {
@@ -53,34 +54,38 @@ impl<'a> Day<'a> for Day07 {
     const DAY: usize = 7;
 
     type Input = Vec<([usize; 5], usize)>;
-    type ProcessedInput = Vec<([usize; 5], usize)>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .trim()
             .lines()
             .map(|line| {
-                let (hand, bid) = line.split_once(' ').unwrap();
-                (hand_values(hand), bid.parse().unwrap())
+                let (hand, bid) = line.split_once(' ').expected("hand and bid")?;
+                Ok((hand_values(hand)?, bid.parse()?))
             })
             .collect()
     }
 
-    fn solve_part1(mut input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = score(&mut input, false);
-        (input, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(score(&mut input.clone(), false).into())
     }
 
-    fn solve_part2(mut input: Self::ProcessedInput) -> String {
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let joker_val = |n: usize| match n.cmp(&9) {
             Ordering::Greater => n,
             Ordering::Equal => 0,
             Ordering::Less => n + 1,
         };
-        input
+        let mut hands = input.clone();
+        hands
             .iter_mut()
             .for_each(|(h, _b)| (0..5).for_each(|i| h[i] = joker_val(h[i])));
-        score(&mut input, true).to_string()
+        Ok(score(&mut hands, true).into())
     }
 }
 
@@ -99,9 +104,9 @@ mod test_day07 {
 
     #[test]
     fn test_day07_examples() {
-        let input = Day07::parse(EXAMPLE);
-        let (input, part1) = Day07::solve_part1(input);
-        let part2 = Day07::solve_part2(input);
+        let input = Day07::parse(EXAMPLE).unwrap();
+        let part1 = Day07::part1(&input, &()).unwrap();
+        let part2 = Day07::part2(&input, &()).unwrap();
         assert_eq!(part1, "6440");
         assert_eq!(part2, "5905");
     }
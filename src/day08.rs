@@ -1,60 +1,215 @@
 use std::collections::HashMap;
 
-use num::integer;
+use itertools::Itertools;
 
-use crate::day::Day;
+use crate::{
+    day::{Answer, Day, Expected, ParseError, SolveError},
+    trace,
+    util::{
+        cycle::{self, Cycle},
+        math,
+    },
+};
 
+#[derive(Debug, Clone)]
 pub struct Input {
     instr: Vec<usize>,
     map: HashMap<[u8; 3], [[u8; 3]; 2]>,
 }
 
-fn steps_until<P: Fn([u8; 3]) -> bool>(input: &Input, start: [u8; 3], pred: P) -> usize {
+fn name(node: [u8; 3]) -> String {
+    String::from_utf8_lossy(&node).into_owned()
+}
+
+fn walk(
+    input: &Input,
+    start: [u8; 3],
+) -> impl '_ + Iterator<Item = Result<(usize, [u8; 3]), SolveError>> {
     let mut node = start;
-    for (i, &lr) in input.instr.iter().cycle().enumerate() {
-        if pred(node) {
-            return i;
+    input.instr.iter().cycle().enumerate().map(move |(i, &lr)| {
+        trace!(Trace, "visit", node = name(node), step = i);
+        let visited = node;
+        let next = input.map.get(&node);
+        node = next.ok_or_else(|| SolveError::new(format!("no node {}", name(node))))?[lr];
+        Ok((i, visited))
+    })
+}
+
+// where a walk settles into a loop of (node, instruction) states, and the steps up to the
+// end of its first time round that loop on which it's at a node matching `goal`, where
+// `_` matches any letter
+struct Route {
+    cycle: Cycle,
+    hits: Vec<usize>,
+}
+
+impl Route {
+    // (first step, period) for each way of being at the goal once in the loop; hits that
+    // repeat more often than the loop itself only need their own shorter period
+    fn residues(&self) -> Vec<(i64, i64)> {
+        let Cycle { prefix, period } = self.cycle;
+        let looped = &self.hits[self.hits.partition_point(|&h| h < prefix)..];
+        let shifted = |h: usize, q| prefix + (h - prefix + q) % period;
+        let shortest = (1..period)
+            .filter(|q| period % q == 0)
+            .find(|&q| {
+                looped
+                    .iter()
+                    .all(|&h| looped.binary_search(&shifted(h, q)).is_ok())
+            })
+            .unwrap_or(period);
+        let looped = looped.iter().take_while(|&&h| h < prefix + shortest);
+        looped.map(|&h| (h as i64, shortest as i64)).collect()
+    }
+}
+
+fn matches(node: [u8; 3], goal: &[u8; 3]) -> bool {
+    node.iter().zip(goal).all(|(&n, &g)| g == b'_' || n == g)
+}
+
+// the loop of (node, instruction) states a walk from `start` ends up in
+fn settle(input: &Input, start: [u8; 3]) -> Result<Cycle, SolveError> {
+    if input.instr.is_empty() {
+        return Err(SolveError::new("no instructions"));
+    }
+    let step = |(node, i): &mut ([u8; 3], usize)| {
+        // a missing node just stops the walk here, and `walk` reports it if it's reached
+        *node = input
+            .map
+            .get(node)
+            .map_or(*node, |next| next[input.instr[*i]]);
+        *i = (*i + 1) % input.instr.len();
+    };
+    Ok(cycle::find(&(start, 0), step))
+}
+
+fn route(input: &Input, start: [u8; 3], goal: &[u8; 3]) -> Result<Route, SolveError> {
+    let cycle = settle(input, start)?;
+    let mut hits = Vec::new();
+    for step in walk(input, start).take(cycle.prefix + cycle.period) {
+        let (i, node) = step?;
+        if matches(node, goal) {
+            hits.push(i);
+        }
+    }
+    trace!(
+        Debug,
+        "route",
+        start = name(start),
+        prefix = cycle.prefix,
+        period = cycle.period,
+        hits = hits
+    );
+    Ok(Route { cycle, hits })
+}
+
+fn steps_until(input: &Input, start: [u8; 3], goal: &[u8; 3]) -> Result<usize, SolveError> {
+    let cycle = settle(input, start)?;
+    for step in walk(input, start).take(cycle.prefix + cycle.period) {
+        let (steps, node) = step?;
+        if matches(node, goal) {
+            trace!(Debug, "reached", start = name(start), steps = steps);
+            return Ok(steps);
         }
-        node = input.map.get(&node).unwrap()[lr];
     }
-    unreachable!()
+    let (start, goal) = (name(start), name(*goal));
+    Err(SolveError::new(format!("{} never reaches {}", start, goal)))
 }
 
 pub struct Day08;
 
 impl<'a> Day<'a> for Day08 {
     const DAY: usize = 8;
+    const QUERIES: &'static [(&'static str, &'static str)] = &[(
+        "steps <node>",
+        "steps from a node until reaching one ending in Z",
+    )];
 
     type Input = Input;
-    type ProcessedInput = Input;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        let (instr, map) = input.split_once("\n\n").unwrap();
-        let instr = instr.trim().chars().map(|c| (c == 'R') as usize).collect();
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        let (instr, map) = input
+            .split_once("\n\n")
+            .expected("blank line after instructions")?;
+        let instr = instr
+            .trim()
+            .chars()
+            .map(|c| "LR".find(c).expected("'L' or 'R'"))
+            .collect::<Result<_, _>>()?;
         let map = map
             .trim()
             .lines()
             .map(|line| {
-                let (p, ls) = line.split_once(" = ").unwrap();
-                let (l, r) = ls.split_once(", ").unwrap();
-                let encode = |s: &str| [s.as_bytes()[0], s.as_bytes()[1], s.as_bytes()[2]];
-                (encode(p), [encode(&l[1..]), encode(&r[..r.len() - 1])])
+                let (p, ls) = line.split_once(" = ").expected("' = '")?;
+                let ls = ls.strip_prefix('(').and_then(|ls| ls.strip_suffix(')'));
+                let (l, r) = ls
+                    .and_then(|ls| ls.split_once(", "))
+                    .expected("(left, right)")?;
+                let encode = |s: &str| s.as_bytes().try_into().ok().expected("three letter node");
+                Ok((encode(p)?, [encode(l)?, encode(r)?]))
             })
-            .collect();
-        Input { instr, map }
+            .collect::<Result<_, ParseError>>()?;
+        Ok(Input { instr, map })
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = steps_until(&input, [b'A'; 3], |n| n == [b'Z'; 3]);
-        (input, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(steps_until(input, *b"AAA", b"ZZZ")?.into())
+    }
+
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let starts = input.map.keys().filter(|&&n| matches(n, b"__A"));
+        let routes = starts
+            .map(|&n| route(input, n, b"__Z"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if routes.is_empty() {
+            return Err(SolveError::new("no ghosts start at __A"));
+        }
+        // until every ghost is in its loop the steps have to be checked one by one
+        let settled = routes.iter().map(|r| r.cycle.prefix).max().unwrap_or(0);
+        let at_goal = |t| {
+            routes
+                .iter()
+                .all(|r| r.hits.binary_search(&r.cycle.reduce(t)).is_ok())
+        };
+        if let Some(steps) = (0..settled).find(|&t| at_goal(t)) {
+            return Ok(steps.into());
+        }
+        // after that each ghost is at __Z on some residues modulo its period, so try every
+        // way of picking one residue per ghost
+        let residues = routes.iter().map(Route::residues);
+        let (mut best, mut overflowed) = (None, false);
+        for picks in residues.multi_cartesian_product() {
+            match math::first_simultaneous(picks.iter().copied()) {
+                Some(steps) => best = Some(best.map_or(steps, |b: i64| b.min(steps))),
+                None => overflowed |= math::crt_solvable(&picks),
+            }
+        }
+        match (best, overflowed) {
+            (_, true) => Err(SolveError::new(
+                "steps until every ghost is at __Z overflowed",
+            )),
+            (Some(steps), false) => Ok(steps.into()),
+            (None, false) => Err(SolveError::new("the ghosts are never all at __Z at once")),
+        }
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        let ns = input.map.keys().filter(|n| n[2] == b'A');
-        // assumes __A -> __Z -> __Z both paths same length & instructions loop
-        ns.map(|&n| steps_until(&input, n, |n| n[2] == b'Z'))
-            .fold(1, integer::lcm)
-            .to_string()
+    fn query(input: &Self::Input, command: &str, args: &[&str]) -> Result<String, String> {
+        match (command, args) {
+            ("steps", [node]) => {
+                let node = node.as_bytes().try_into().map_err(|_| "Expected a node")?;
+                if !input.map.contains_key(&node) {
+                    return Err("No such node".to_string());
+                }
+                let steps = steps_until(input, node, b"__Z");
+                steps.map(|n| n.to_string()).map_err(|err| err.to_string())
+            }
+            _ => Err(format!("Unknown command \"{}\"", command)),
+        }
     }
 }
 
@@ -84,14 +239,44 @@ mod test_day08 {
         XXX = (XXX, XXX)
     "};
 
+    const EXAMPLE3: &str = indoc! {"
+        L
+
+        11A = (11Z, 11Z)
+        11Z = (12Z, 12Z)
+        12Z = (11B, 11B)
+        11B = (11Z, 11Z)
+        22A = (22B, 22B)
+        22B = (22C, 22C)
+        22C = (22Z, 22Z)
+        22Z = (22D, 22D)
+        22D = (22Z, 22Z)
+    "};
+
     #[test]
     fn test_day08_examples() {
-        let input = Day08::parse(EXAMPLE1);
-        let (_, part1) = Day08::solve_part1(input);
+        let input = Day08::parse(EXAMPLE1).unwrap();
+        let part1 = Day08::part1(&input, &()).unwrap();
         assert_eq!(part1, "6");
 
-        let input = Day08::parse(EXAMPLE2);
-        let part2 = Day08::solve_part2(input);
+        let input = Day08::parse(EXAMPLE2).unwrap();
+        let part2 = Day08::part2(&input, &()).unwrap();
         assert_eq!(part2, "6");
+
+        // 11A is at __Z on two steps out of every three, which the first two hits alone
+        // don't show
+        let input = Day08::parse(EXAMPLE3).unwrap();
+        assert_eq!(Day08::part2(&input, &()).unwrap(), "5");
+        let input = Day08::parse(
+            &EXAMPLE3.replace("22D = (22Z, 22Z)", "22D = (22E, 22E)\n22E = (22Z, 22Z)"),
+        );
+        let err = Day08::part2(&input.unwrap(), &()).unwrap_err();
+        assert_eq!(err.to_string(), "the ghosts are never all at __Z at once");
+
+        let input = Day08::parse(&EXAMPLE1.replace("(ZZZ, ZZZ)", "(ZZZ, YYY)")).unwrap();
+        assert!(Day08::part1(&input, &()).is_ok());
+        let input = Day08::parse(&EXAMPLE1.replace("(AAA, ZZZ)", "(AAA, YYY)")).unwrap();
+        let err = Day08::part1(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "no node YYY");
     }
 }
@@ -1,14 +1,14 @@
-use crate::day::Day;
+use num::BigInt;
 
-fn extrapolate(ns: &mut [i32]) -> (i32, i32) {
-    if ns.iter().copied().all(|n| n == 0) {
-        (0, 0)
-    } else {
-        let (first, last_idx) = (ns[0], ns.len() - 1);
-        (0..last_idx).for_each(|i| ns[i] = ns[i + 1] - ns[i]);
-        let above = extrapolate(&mut ns[..last_idx]);
-        (first - above.0, ns[last_idx] + above.1)
-    }
+use crate::{
+    day::{Answer, Day, ParseError, SolveError},
+    util::poly::Polynomial,
+};
+
+// the values before the first and after the last
+fn extrapolate(ns: &[i32]) -> (BigInt, BigInt) {
+    let poly = Polynomial::through(ns);
+    (poly.eval(-1), poly.eval(ns.len()))
 }
 
 pub struct Day09;
@@ -17,30 +17,35 @@ impl<'a> Day<'a> for Day09 {
     const DAY: usize = 9;
 
     type Input = Vec<Vec<i32>>;
-    type ProcessedInput = i32;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .trim()
             .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|n| n.parse().unwrap())
-                    .collect()
-            })
-            .collect()
+            .map(|line| line.split_whitespace().map(str::parse).collect())
+            .collect::<Result<_, _>>()
+            .map_err(ParseError::from)
+    }
+
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let (p2, p1) = input
-            .into_iter()
-            .map(|mut ns| extrapolate(&mut ns))
-            .fold((0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
-        (p2, p1.to_string())
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
+            .map(|ns| extrapolate(ns).1)
+            .sum::<BigInt>()
+            .into())
     }
 
-    fn solve_part2(p2: Self::ProcessedInput) -> String {
-        p2.to_string()
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
+            .map(|ns| extrapolate(ns).0)
+            .sum::<BigInt>()
+            .into())
     }
 }
 
@@ -57,9 +62,9 @@ mod test_day09 {
 
     #[test]
     fn test_day09_examples() {
-        let input = Day09::parse(EXAMPLE);
-        let (input, part1) = Day09::solve_part1(input);
-        let part2 = Day09::solve_part2(input);
+        let input = Day09::parse(EXAMPLE).unwrap();
+        let part1 = Day09::part1(&input, &()).unwrap();
+        let part2 = Day09::part2(&input, &()).unwrap();
         assert_eq!(part1, "114");
         assert_eq!(part2, "2");
     }
@@ -1,6 +1,13 @@
-use std::collections::HashSet;
+use std::io;
 
-use crate::{day::Day, util::LineGrid};
+use crate::{
+    day::{Answer, Checked, Day, ParseError, SolveError},
+    util::{
+        geometry::{self, Location},
+        LineGrid,
+    },
+    viz::{Backend, Frame, Visualize},
+};
 
 fn step(grid: &LineGrid<'_>, pos: &mut (i32, i32), dir: (i32, i32)) -> Option<(i32, i32)> {
     pos.0 += dir.0;
@@ -25,61 +32,81 @@ fn step(grid: &LineGrid<'_>, pos: &mut (i32, i32), dir: (i32, i32)) -> Option<(i
     }
 }
 
+// the loop's cells in order, starting from S
+fn find_loop(grid: &LineGrid<'_>) -> Result<Vec<(i64, i64)>, SolveError> {
+    let start = grid
+        .iter()
+        .find(|&(_, _, c)| c == 'S')
+        .ok_or_else(|| SolveError::new("no start tile"))?;
+    let start_pos = (start.0 as i32, start.1 as i32);
+    let mut pos = start_pos;
+    let mut pipes = vec![(pos.0 as i64, pos.1 as i64)];
+    let mut dir = [(1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .find_map(|d| step(grid, &mut pos, d))
+        .ok_or_else(|| SolveError::new("no pipe connects to the start"))?;
+    while pos != start_pos {
+        pipes.push((pos.0 as i64, pos.1 as i64));
+        dir = step(grid, &mut pos, dir)
+            .ok_or_else(|| SolveError::new(format!("pipe at {:?} leads nowhere", pos)))?;
+    }
+    Ok(pipes)
+}
+
 pub struct Day10;
 
 impl<'a> Day<'a> for Day10 {
     const DAY: usize = 10;
 
     type Input = LineGrid<'a>;
-    type ProcessedInput = (LineGrid<'a>, HashSet<(i32, i32)>, bool);
+    type Shared = Vec<(i64, i64)>;
 
-    fn parse(input: &'a str) -> Self::Input {
-        LineGrid::new(input)
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        LineGrid::parse(input)
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let start = input.iter().find(|&(_, _, c)| c == 'S').unwrap();
-        let start_pos = (start.0 as i32, start.1 as i32);
-        let mut pipes = HashSet::new();
-        let mut pos = start_pos;
-        pipes.insert(pos);
-        let (start_dir, mut dir) = [(1, 0), (0, 1), (0, -1)]
-            .into_iter()
-            .find_map(|d| step(&input, &mut pos, d).map(|dir| (d, dir)))
-            .unwrap();
-        while pos != start_pos {
-            pipes.insert(pos);
-            let Some(d) = step(&input, &mut pos, dir) else {
-                panic!("no connection")
-            };
-            dir = d;
-        }
-        let start_is_ilj = match (dir, start_dir) {
-            (a, b) if a == b && a.1 == 0 => true,
-            ((1, 0), (0, 1)) | ((0, -1), (-1, 0)) | ((0, 1), (-1, 0)) => true,
-            _ => false,
-        };
-        let ans = pipes.len() / 2;
-        ((input, pipes, start_is_ilj), ans.to_string())
+    fn prepare(input: &Self::Input) -> Result<Self::Shared, SolveError> {
+        find_loop(input)
+    }
+
+    fn part1(_: &Self::Input, pipes: &Self::Shared) -> Result<Answer, SolveError> {
+        Ok((pipes.len() / 2).into())
     }
 
-    fn solve_part2((input, pipes, start_is_ilj): Self::ProcessedInput) -> String {
-        let mut count = 0;
-        for r in 0..input.height() as i32 {
-            let mut inside = false;
-            for c in 0..input.width() as i32 {
-                if pipes.contains(&(r, c)) {
-                    match input.try_get(r, c) {
-                        Some('|') | Some('L') | Some('J') => inside = !inside,
-                        Some('S') if start_is_ilj => inside = !inside,
-                        _ => (),
-                    };
-                } else if inside {
-                    count += 1;
-                }
+    fn part2(_: &Self::Input, pipes: &Self::Shared) -> Result<Answer, SolveError> {
+        let enclosed = geometry::interior_points(pipes).checked("enclosed tiles")?;
+        Ok(enclosed.into())
+    }
+}
+
+impl<'a> Visualize<'a> for Day10 {
+    fn visualize(input: &Self::Input, backend: &mut dyn Backend) -> io::Result<()> {
+        let pipes = find_loop(input).map_err(io::Error::other)?;
+        let n = pipes.len();
+        let corners = (0..n)
+            .filter(|&i| {
+                let (a, b, c) = (pipes[(i + n - 1) % n], pipes[i], pipes[(i + 1) % n]);
+                (b.0 - a.0, b.1 - a.1) != (c.0 - b.0, c.1 - b.1)
+            })
+            .map(|i| pipes[i])
+            .collect::<Vec<_>>();
+        let mut frame = Frame::from_grid(input, |c| match c {
+            '.' => [16, 16, 16],
+            _ => [64, 64, 64],
+        });
+        for &(r, c) in pipes.iter() {
+            let color = match input.get(r as usize, c as usize) {
+                Some('S') => [255, 64, 64],
+                _ => [255, 160, 0],
+            };
+            frame.set(r as usize, c as usize, color);
+        }
+        for (r, c, _) in input.iter() {
+            if geometry::locate(&corners, (r as i64, c as i64)) == Location::Inside {
+                frame.set(r, c, [64, 200, 96]);
             }
         }
-        count.to_string()
+        backend.frame(&frame)
     }
 }
 
@@ -111,13 +138,17 @@ mod test_day10 {
 
     #[test]
     fn test_day10_examples() {
-        let input = Day10::parse(EXAMPLE1);
-        let (_, part1) = Day10::solve_part1(input);
+        let input = Day10::parse(EXAMPLE1).unwrap();
+        let shared = Day10::prepare(&input).unwrap();
+        let part1 = Day10::part1(&input, &shared).unwrap();
         assert_eq!(part1, "8");
 
-        let input = Day10::parse(EXAMPLE2);
-        let (input, _) = Day10::solve_part1(input);
-        let part2 = Day10::solve_part2(input);
+        let input = Day10::parse(EXAMPLE2).unwrap();
+        let shared = Day10::prepare(&input).unwrap();
+        let part2 = Day10::part2(&input, &shared).unwrap();
         assert_eq!(part2, "10");
+
+        assert!(Day10::prepare(&LineGrid::new("F7\nLJ")).is_err());
+        assert!(Day10::prepare(&LineGrid::new("S7\nL-")).is_err());
     }
 }
@@ -1,4 +1,7 @@
-use crate::{day::Day, util::LineGrid};
+use crate::{
+    day::{Answer, Day, ParseError, SolveError},
+    util::LineGrid,
+};
 
 fn expand_gap(mut start: usize, mut end: usize, expands: &[usize], mult: u64) -> u64 {
     if start > end {
@@ -38,13 +41,14 @@ impl<'a, const N: u64> Day<'a> for Day11Generic<N> {
     const DAY: usize = 11;
 
     type Input = LineGrid<'a>;
-    type ProcessedInput = (Vec<(usize, usize)>, Vec<usize>, Vec<usize>);
+    type Shared = (Vec<(usize, usize)>, Vec<usize>, Vec<usize>);
 
-    fn parse(input: &'a str) -> Self::Input {
-        LineGrid::new(input.trim())
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        LineGrid::parse(input)
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
+    // the galaxies, and the empty rows and columns between them
+    fn prepare(input: &Self::Input) -> Result<Self::Shared, SolveError> {
         let galaxies = input
             .iter()
             .filter_map(|(r, c, ch)| (ch == '#').then_some((r, c)))
@@ -57,12 +61,21 @@ impl<'a, const N: u64> Day<'a> for Day11Generic<N> {
             .collect::<Vec<_>>();
         empty_rows.sort_unstable();
         empty_cols.sort_unstable();
-        let ans = count_distances(&galaxies, &empty_rows, &empty_cols, 2);
-        ((galaxies, empty_rows, empty_cols), ans.to_string())
+        Ok((galaxies, empty_rows, empty_cols))
     }
 
-    fn solve_part2((galaxies, empty_rows, empty_cols): Self::ProcessedInput) -> String {
-        count_distances(&galaxies, &empty_rows, &empty_cols, N).to_string()
+    fn part1(
+        _: &Self::Input,
+        (galaxies, empty_rows, empty_cols): &Self::Shared,
+    ) -> Result<Answer, SolveError> {
+        Ok(count_distances(galaxies, empty_rows, empty_cols, 2).into())
+    }
+
+    fn part2(
+        _: &Self::Input,
+        (galaxies, empty_rows, empty_cols): &Self::Shared,
+    ) -> Result<Answer, SolveError> {
+        Ok(count_distances(galaxies, empty_rows, empty_cols, N).into())
     }
 }
 
@@ -70,6 +83,7 @@ impl<'a, const N: u64> Day<'a> for Day11Generic<N> {
 mod test_day11 {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc! {"
         ...#......
@@ -86,10 +100,51 @@ mod test_day11 {
 
     #[test]
     fn test_day11_examples() {
-        let input = Day11Generic::<100>::parse(EXAMPLE);
-        let (input, part1) = Day11Generic::<100>::solve_part1(input);
-        let part2 = Day11Generic::<100>::solve_part2(input);
+        let input = Day11Generic::<100>::parse(EXAMPLE).unwrap();
+        let shared = Day11Generic::<100>::prepare(&input).unwrap();
+        let part1 = Day11Generic::<100>::part1(&input, &shared).unwrap();
+        let part2 = Day11Generic::<100>::part2(&input, &shared).unwrap();
         assert_eq!(part1, "374");
         assert_eq!(part2, "8410");
     }
+
+    fn brute_count_distances(galaxies: &[(usize, usize)], size: usize, mult: u64) -> u64 {
+        let expand = |coord: fn(&(usize, usize)) -> usize| {
+            let mut offset = 0;
+            let mut expanded = vec![0; size];
+            for (i, e) in expanded.iter_mut().enumerate() {
+                *e = i as u64 + offset;
+                if !galaxies.iter().any(|g| coord(g) == i) {
+                    offset += mult - 1;
+                }
+            }
+            expanded
+        };
+        let (rows, cols) = (expand(|g| g.0), expand(|g| g.1));
+        let mut total = 0;
+        for (i, &(r0, c0)) in galaxies.iter().enumerate() {
+            for &(r1, c1) in &galaxies[..i] {
+                total += rows[r0].abs_diff(rows[r1]) + cols[c0].abs_diff(cols[c1]);
+            }
+        }
+        total
+    }
+
+    proptest! {
+        #[test]
+        fn test_day11_count_distances(
+            galaxies in prop::collection::btree_set((0..12usize, 0..12usize), 0..20),
+            mult in 1..100u64,
+        ) {
+            let galaxies = galaxies.into_iter().collect::<Vec<_>>();
+            let empty = |coord: fn(&(usize, usize)) -> usize| {
+                (0..12).filter(|&i| !galaxies.iter().any(|g| coord(g) == i)).collect::<Vec<_>>()
+            };
+            let (empty_rows, empty_cols) = (empty(|g| g.0), empty(|g| g.1));
+            prop_assert_eq!(
+                count_distances(&galaxies, &empty_rows, &empty_cols, mult),
+                brute_count_distances(&galaxies, 12, mult)
+            );
+        }
+    }
 }
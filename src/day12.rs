@@ -1,19 +1,28 @@
-use std::{collections::HashMap, iter};
+use std::iter;
 
 use itertools::{intersperse, Itertools};
 
-use crate::day::Day;
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    trace,
+    util::{hash::FastHashMap, math, memo::Memo},
+};
 
-fn count(row: &[u8], groups: &[usize], memo: &mut HashMap<(Vec<u8>, Vec<usize>), u64>) -> u64 {
-    let k = (row.to_vec(), groups.to_vec());
-    memo.get(&k).copied().unwrap_or_else(|| {
+// memoised on the lengths of the suffixes left, so one memo serves a single row; None
+// if the count overflows
+fn count(
+    row: &[u8],
+    groups: &[usize],
+    memo: &mut Memo<(usize, usize), Option<u64>>,
+) -> Option<u64> {
+    memo.get_or_compute((row.len(), groups.len()), |memo| {
         if groups.is_empty() {
-            return row.iter().copied().all(|c| c != b'#') as u64;
+            return Some(row.iter().copied().all(|c| c != b'#') as u64);
         }
         if row.len() < groups[0] {
-            return 0;
+            return Some(0);
         }
-        let mut nexts: HashMap<usize, u64> = HashMap::new();
+        let mut nexts = FastHashMap::<usize, u64>::default();
         for i in 0..row.len() - groups[0] + 1 {
             let (end, len) = (i + groups[0], row.len());
             if i > 0 && row[i - 1] == b'#' {
@@ -26,12 +35,10 @@ fn count(row: &[u8], groups: &[usize], memo: &mut HashMap<(Vec<u8>, Vec<usize>),
             let j = (j..len).find(|&j| row[j] != b'.').unwrap_or(len);
             *nexts.entry(j).or_default() += 1;
         }
-        let result = nexts
-            .into_iter()
-            .map(|(j, n)| n * count(&row[j..], &groups[1..], memo))
-            .sum();
-        memo.insert(k, result);
-        result
+        nexts.into_iter().try_fold(0u64, |total, (j, n)| {
+            let ways = n.checked_mul(count(&row[j..], &groups[1..], memo)?)?;
+            total.checked_add(ways)
+        })
     })
 }
 
@@ -41,39 +48,46 @@ impl<'a> Day<'a> for Day12 {
     const DAY: usize = 12;
 
     type Input = Vec<(&'a [u8], Vec<usize>)>;
-    type ProcessedInput = Vec<(&'a [u8], Vec<usize>)>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .trim()
             .lines()
             .map(|line| {
-                let (springs, groups) = line.split_once(' ').unwrap();
-                let groups = groups.split(',').map(|n| n.parse().unwrap()).collect();
-                (springs.as_bytes(), groups)
+                let (springs, groups) = line.split_once(' ').expected("springs and groups")?;
+                let groups = groups
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()?;
+                Ok((springs.as_bytes(), groups))
             })
             .collect()
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = input
-            .iter()
-            .map(move |(r, g)| count(r, g, &mut HashMap::new()))
-            .sum::<u64>();
-        (input, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        let mut memo = HashMap::new();
-        input
-            .into_iter()
-            .map(move |(r, g)| {
-                let r5 = intersperse(iter::repeat(r.to_vec()).take(5), vec![b'?']);
-                let g5 = iter::repeat(g).take(5);
-                count(&r5.concat(), &g5.concat(), &mut memo)
-            })
-            .sum::<u64>()
-            .to_string()
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let counts = input.iter().map(|(r, g)| count(r, g, &mut Memo::new()));
+        let counts = counts.collect::<Option<Vec<_>>>().checked("arrangements")?;
+        let ans = math::checked_sum(counts).checked("sum of arrangements")?;
+        Ok(ans.into())
+    }
+
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let counts = input.iter().map(|(r, g)| {
+            let r5 = intersperse(iter::repeat_n(r.to_vec(), 5), vec![b'?']);
+            let g5 = iter::repeat_n(g.clone(), 5);
+            let mut memo = Memo::new();
+            let n = count(&r5.concat(), &g5.concat(), &mut memo);
+            trace!(Trace, "counted", hits = memo.hits(), misses = memo.misses());
+            n
+        });
+        let counts = counts.collect::<Option<Vec<_>>>().checked("arrangements")?;
+        let ans = math::checked_sum(counts).checked("sum of arrangements")?;
+        Ok(ans.into())
     }
 }
 
@@ -93,10 +107,15 @@ mod test_day12 {
 
     #[test]
     fn test_day12_examples() {
-        let input = Day12::parse(EXAMPLE);
-        let (input, part1) = Day12::solve_part1(input);
-        let part2 = Day12::solve_part2(input);
+        let input = Day12::parse(EXAMPLE).unwrap();
+        let part1 = Day12::part1(&input, &()).unwrap();
+        let part2 = Day12::part2(&input, &()).unwrap();
         assert_eq!(part1, "21");
         assert_eq!(part2, "525152");
+
+        // C(141, 60) arrangements of 60 single springs in 200 unknowns
+        let input = format!("{} {}", "?".repeat(200), vec!["1"; 60].join(","));
+        let err = Day12::part1(&Day12::parse(&input).unwrap(), &()).unwrap_err();
+        assert_eq!(err.to_string(), "arrangements overflowed");
     }
 }
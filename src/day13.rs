@@ -1,4 +1,7 @@
-use crate::{day::Day, util::LineGrid};
+use crate::{
+    day::{Answer, Checked, Day, ParseError, SolveError},
+    util::{math, parse, LineGrid},
+};
 
 fn find_mirror<I: Iterator<Item = char>, F: Fn(usize) -> I>(
     span: usize,
@@ -18,32 +21,37 @@ fn score_mirror(grid: &LineGrid<'_>, smudge: usize) -> Option<usize> {
         .or_else(|| find_mirror(grid.width(), |c| grid.iter_col(c), smudge))
 }
 
+fn total_score(grids: &[LineGrid<'_>], smudge: usize) -> Result<usize, SolveError> {
+    let scores = grids.iter().enumerate().map(|(i, g)| {
+        score_mirror(g, smudge)
+            .ok_or_else(|| SolveError::new(format!("no mirror in pattern {}", i + 1)))
+    });
+    let scores = scores.collect::<Result<Vec<_>, _>>()?;
+    math::checked_sum(scores).checked("sum of mirror scores")
+}
+
 pub struct Day13;
 
 impl<'a> Day<'a> for Day13 {
     const DAY: usize = 13;
 
     type Input = Vec<LineGrid<'a>>;
-    type ProcessedInput = Vec<LineGrid<'a>>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        input.trim().split("\n\n").map(LineGrid::new).collect()
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        parse::paragraphs(input).map(LineGrid::parse).collect()
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = input
-            .iter()
-            .map(|g| score_mirror(g, 0).unwrap())
-            .sum::<usize>();
-        (input, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        input
-            .iter()
-            .map(|g| score_mirror(g, 1).unwrap())
-            .sum::<usize>()
-            .to_string()
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(total_score(input, 0)?.into())
+    }
+
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(total_score(input, 1)?.into())
     }
 }
 
@@ -72,10 +80,14 @@ mod test_day13 {
 
     #[test]
     fn test_day13_examples() {
-        let input = Day13::parse(EXAMPLE);
-        let (input, part1) = Day13::solve_part1(input);
-        let part2 = Day13::solve_part2(input);
+        let input = Day13::parse(EXAMPLE).unwrap();
+        let part1 = Day13::part1(&input, &()).unwrap();
+        let part2 = Day13::part2(&input, &()).unwrap();
         assert_eq!(part1, "405");
         assert_eq!(part2, "400");
+
+        let input = Day13::parse("#.#\n.#.\n\n##\n..").unwrap();
+        let err = Day13::part1(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "no mirror in pattern 1");
     }
 }
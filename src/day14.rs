@@ -1,74 +1,76 @@
-use std::collections::HashMap;
-
-use crate::day::Day;
+use crate::{
+    day::{Answer, Day, ParseError, SolveError},
+    trace,
+    util::{cycle, Grid},
+};
 
 const STEPS: usize = 1000000000;
 
-fn push_round<F: Fn(usize) -> (usize, usize)>(len: usize, idx: F, grid: &mut [Vec<u8>]) {
+fn push_round<F: Fn(usize) -> (usize, usize)>(len: usize, idx: F, grid: &mut Grid<u8>) {
     let mut eaten = 0;
     for i in 0..len {
-        if grid[idx(i).0][idx(i).1] == b'O' {
-            grid[idx(i).0][idx(i).1] = b'.';
+        if grid[idx(i)] == b'O' {
+            grid[idx(i)] = b'.';
             eaten += 1;
-        } else if grid[idx(i).0][idx(i).1] == b'#' && eaten > 0 {
-            (i - eaten..i).for_each(|j| grid[idx(j).0][idx(j).1] = b'O');
+        } else if grid[idx(i)] == b'#' && eaten > 0 {
+            (i - eaten..i).for_each(|j| grid[idx(j)] = b'O');
             eaten = 0;
         }
     }
     if eaten > 0 {
-        (len - eaten..len).for_each(|j| grid[idx(j).0][idx(j).1] = b'O');
+        (len - eaten..len).for_each(|j| grid[idx(j)] = b'O');
     }
 }
 
-fn score(width: usize, height: usize, grid: &[Vec<u8>]) -> usize {
-    (0..width)
-        .map(|r| (0..height).filter(|&c| grid[r][c] == b'O').count() * (height - r))
+fn score(grid: &Grid<u8>) -> usize {
+    grid.rows()
+        .enumerate()
+        .map(|(r, row)| row.iter().filter(|&&c| c == b'O').count() * (grid.height() - r))
         .sum::<usize>()
 }
 
+fn spin(grid: &mut Grid<u8>) {
+    let (w, h) = (grid.width(), grid.height());
+    (0..w).for_each(|c| push_round(h, |r| (h - 1 - r, c), grid));
+    (0..h).for_each(|r| push_round(w, |c| (r, w - 1 - c), grid));
+    (0..w).for_each(|c| push_round(h, |r| (r, c), grid));
+    (0..h).for_each(|r| push_round(w, |c| (r, c), grid));
+}
+
 pub struct Day14;
 
 impl<'a> Day<'a> for Day14 {
     const DAY: usize = 14;
 
-    type Input = Vec<Vec<u8>>;
-    type ProcessedInput = Vec<Vec<u8>>;
+    type Input = Grid<u8>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        input
-            .trim()
-            .lines()
-            .map(|s| s.as_bytes().to_vec())
-            .collect()
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        Grid::parse(input, |c| match c {
+            'O' | '#' | '.' => Ok(c as u8),
+            _ => Err(ParseError::new(format!("unexpected rock \"{}\"", c))),
+        })
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let (w, h, mut grid) = (input[0].len(), input.len(), input.clone());
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let (w, h, mut grid) = (input.width(), input.height(), input.clone());
         (0..w).for_each(|c| push_round(h, |r| (h - 1 - r, c), &mut grid));
-        (input, score(w, h, &grid).to_string())
+        Ok(score(&grid).into())
     }
 
-    fn solve_part2(mut grid: Self::ProcessedInput) -> String {
-        let (w, h, mut seen) = (grid[0].len(), grid.len(), HashMap::new());
-        seen.insert(grid.clone(), 0);
-        let mut i = 0;
-        let mut found_cycle = false;
-        while i < STEPS {
-            (0..w).for_each(|c| push_round(h, |r| (h - 1 - r, c), &mut grid));
-            (0..h).for_each(|r| push_round(h, |c| (r, w - 1 - c), &mut grid));
-            (0..w).for_each(|c| push_round(h, |r| (r, c), &mut grid));
-            (0..h).for_each(|r| push_round(h, |c| (r, c), &mut grid));
-            i += 1;
-            if !found_cycle {
-                if let Some(j) = seen.get(&grid) {
-                    found_cycle = true;
-                    i = STEPS - ((STEPS - i) % (i - j));
-                } else {
-                    seen.insert(grid.clone(), i);
-                }
-            }
-        }
-        score(w, h, &grid).to_string()
+    fn part2(grid: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let cycle = cycle::brent(grid, spin);
+        trace!(
+            Debug,
+            "found cycle",
+            start = cycle.prefix,
+            period = cycle.period
+        );
+        Ok(score(&cycle::state_after(grid, spin, &cycle, STEPS)).into())
     }
 }
 
@@ -92,10 +94,32 @@ mod test_day14 {
 
     #[test]
     fn test_day14_examples() {
-        let input = Day14::parse(EXAMPLE);
-        let (input, part1) = Day14::solve_part1(input);
-        let part2 = Day14::solve_part2(input);
+        let input = Day14::parse(EXAMPLE).unwrap();
+        let part1 = Day14::part1(&input, &()).unwrap();
+        let part2 = Day14::part2(&input, &()).unwrap();
         assert_eq!(part1, "136");
         assert_eq!(part2, "64");
     }
+
+    #[test]
+    fn test_day14_not_square() {
+        // the example's first six rows, where Brent should agree with hashing every state
+        let input = EXAMPLE.lines().take(6).collect::<Vec<_>>().join("\n");
+        let grid = Day14::parse(&input).unwrap();
+        let hashed = cycle::find(&grid, spin);
+        let spun = cycle::state_after(&grid, spin, &hashed, STEPS);
+        let part2 = Day14::part2(&grid, &()).unwrap();
+        assert_eq!(part2, score(&spun).to_string().as_str());
+        assert_eq!(part2, "24");
+    }
+
+    #[test]
+    fn test_day14_spin() {
+        let mut grid = Day14::parse("O.O.O\n.....\n#....").unwrap();
+        spin(&mut grid);
+        assert_eq!(grid, Day14::parse(".....\n....O\n#..OO").unwrap());
+        let mut grid = Day14::parse("O.\n.O\n#.").unwrap();
+        spin(&mut grid);
+        assert_eq!(grid, Day14::parse("..\n.O\n#O").unwrap());
+    }
 }
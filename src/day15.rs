@@ -1,41 +1,80 @@
-use crate::day::Day;
+use crate::{
+    day::{Answer, Day, Expected, ParseError, SolveError},
+    util::Ignore,
+};
 
 fn hash(s: &str) -> u8 {
     s.bytes().fold(0, |h, b| h.wrapping_add(b).wrapping_mul(17))
 }
 
+// the step as written, for part 1, and the lens it removes (None) or puts in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step<'a> {
+    text: &'a str,
+    label: &'a str,
+    focal: Option<usize>,
+}
+
+fn parse_step(text: &str) -> Result<Step<'_>, ParseError> {
+    let i = text.find(['=', '-']).expected("'=' or '-'")?;
+    let label = &text[..i];
+    if label.is_empty() || !label.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Err(ParseError::new(format!(
+            "expected a label of lowercase letters in \"{}\"",
+            text
+        )));
+    }
+    let focal = match &text[i..] {
+        "-" => None,
+        op => op
+            .strip_prefix('=')
+            .and_then(|f| f.parse().ok())
+            .filter(|f| (1..=9).contains(f))
+            .map(Some)
+            .expected("'-' or '=' and a focal length from 1 to 9")?,
+    };
+    Ok(Step { text, label, focal })
+}
+
 pub struct Day15;
 
 impl<'a> Day<'a> for Day15 {
     const DAY: usize = 15;
+    const QUERIES: &'static [(&'static str, &'static str)] =
+        &[("hash <step>", "HASH algorithm value of a step")];
+
+    type Input = Vec<Step<'a>>;
+    type Shared = ();
 
-    type Input = Vec<&'a str>;
-    type ProcessedInput = Vec<&'a str>;
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        input.trim().split(',').map(parse_step).collect()
+    }
 
-    fn parse(input: &'a str) -> Self::Input {
-        input.trim().split(',').collect()
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = input.iter().map(|s| hash(s) as u32).sum::<u32>();
-        (input, ans.to_string())
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(input
+            .iter()
+            .map(|s| hash(s.text) as u32)
+            .sum::<u32>()
+            .into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let mut boxes: [Vec<(&'a str, usize)>; 256] = std::array::from_fn(|_| Vec::new());
-        for cmd in input {
-            let i = cmd.find(|c| c == '=' || c == '-').unwrap();
-            let h = hash(&cmd[..i]) as usize;
-            let old = boxes[h].iter().position(|&(s, _)| s == &cmd[..i]);
-            if cmd.as_bytes()[i] == b'-' {
-                old.map(|j| boxes[h].remove(j));
-            } else {
-                let f = cmd[i + 1..].parse().unwrap();
-                old.map(|j| boxes[h][j] = (&cmd[..i], f))
-                    .unwrap_or_else(|| boxes[h].push((&cmd[..i], f)));
+        for &Step { label, focal, .. } in input {
+            let h = hash(label) as usize;
+            let old = boxes[h].iter().position(|&(s, _)| s == label);
+            match (focal, old) {
+                (None, Some(j)) => boxes[h].remove(j).ignore(),
+                (None, None) => (),
+                (Some(f), Some(j)) => boxes[h][j] = (label, f),
+                (Some(f), None) => boxes[h].push((label, f)),
             }
         }
-        boxes
+        Ok(boxes
             .into_iter()
             .enumerate()
             .map(|(i, bx)| {
@@ -46,7 +85,14 @@ impl<'a> Day<'a> for Day15 {
                         .sum::<usize>()
             })
             .sum::<usize>()
-            .to_string()
+            .into())
+    }
+
+    fn query(_input: &Self::Input, command: &str, args: &[&str]) -> Result<String, String> {
+        match (command, args) {
+            ("hash", [step]) => Ok(hash(step).to_string()),
+            _ => Err(format!("Unknown command \"{}\"", command)),
+        }
     }
 }
 
@@ -61,10 +107,17 @@ mod test_day15 {
 
     #[test]
     fn test_day15_examples() {
-        let input = Day15::parse(EXAMPLE);
-        let (input, part1) = Day15::solve_part1(input);
-        let part2 = Day15::solve_part2(input);
+        let input = Day15::parse(EXAMPLE).unwrap();
+        let part1 = Day15::part1(&input, &()).unwrap();
+        let part2 = Day15::part2(&input, &()).unwrap();
         assert_eq!(part1, "1320");
         assert_eq!(part2, "145");
+
+        let steps = Day15::parse("ab-,cd=7\n").unwrap();
+        assert_eq!(steps[1].text, "cd=7");
+        assert_eq!((steps[0].focal, steps[1].focal), (None, Some(7)));
+        for bad in ["ab", "ab=x", "ab=0", "ab=10", "ab-1", "=4", "Ab=1", ""] {
+            assert!(Day15::parse(bad).is_err(), "{:?} should not parse", bad);
+        }
     }
 }
@@ -1,34 +1,47 @@
-use std::collections::HashMap;
+use std::io;
 
-use crate::{day::Day, util::LineGrid};
+use itertools::Itertools;
 
-const DIRS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+use crate::{
+    day::{Answer, Day, ParseError, SolveError},
+    util::{
+        bitset::{Bits, GrowBitSet},
+        Dir4, LineGrid, Point,
+    },
+    viz::{Backend, Frame, Visualize},
+};
 
-fn energy(grid: &LineGrid<'_>, beam: ((i32, i32), usize)) -> usize {
-    let (mut queue, mut seen) = (vec![beam], HashMap::new());
-    while let Some(((r, c), dir)) = queue.pop() {
-        let dirs: &mut u8 = seen.entry((r, c)).or_default();
-        if *dirs & (1 << dir) != 0 {
+// bit 4 * cell + dir for every beam direction through each cell
+fn energized(grid: &LineGrid<'_>, beam: (Point, Dir4)) -> GrowBitSet {
+    let (mut queue, mut seen) = (vec![beam], GrowBitSet::new());
+    while let Some((pos, dir)) = queue.pop() {
+        let cell = pos.row as usize * grid.width() + pos.col as usize;
+        if !seen.insert(4 * cell + dir as usize) {
             continue;
         }
-        *dirs |= 1 << dir;
-        let mut extend = |dirs: &[usize]| {
+        let mut extend = |dirs: &[Dir4]| {
             queue.extend(
                 dirs.iter()
-                    .map(|&d| ((r + DIRS[d].0, c + DIRS[d].1), d))
-                    .filter(|&((r, c), _)| grid.in_bounds(r, c)),
+                    .map(|&d| (pos + d, d))
+                    .filter(|&(p, _)| grid.in_bounds(p.row, p.col)),
             );
         };
-        match grid.try_get(r, c) {
-            Some('/') => extend(&[3 - dir]),
-            Some('\\') => extend(&[dir + 1 - 2 * (dir % 2)]),
-            Some('-') if dir % 2 == 1 => extend(&[0, 2]),
-            Some('|') if dir % 2 == 0 => extend(&[1, 3]),
+        match grid.get_point(pos) {
+            Some('/') if dir.is_vertical() => extend(&[dir.turn_right()]),
+            Some('/') => extend(&[dir.turn_left()]),
+            Some('\\') if dir.is_vertical() => extend(&[dir.turn_left()]),
+            Some('\\') => extend(&[dir.turn_right()]),
+            Some('-') if dir.is_vertical() => extend(&[Dir4::Left, Dir4::Right]),
+            Some('|') if !dir.is_vertical() => extend(&[Dir4::Up, Dir4::Down]),
             Some(_) => extend(&[dir]),
             None => (),
         }
     }
-    seen.keys().count()
+    seen
+}
+
+fn energy(grid: &LineGrid<'_>, beam: (Point, Dir4)) -> usize {
+    energized(grid, beam).iter().map(|b| b / 4).dedup().count()
 }
 
 pub struct Day16;
@@ -37,24 +50,45 @@ impl<'a> Day<'a> for Day16 {
     const DAY: usize = 16;
 
     type Input = LineGrid<'a>;
-    type ProcessedInput = LineGrid<'a>;
+    type Shared = ();
+
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        LineGrid::parse(input)
+    }
 
-    fn parse(input: &'a str) -> Self::Input {
-        LineGrid::new(input)
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = energy(&input, ((0, 0), 0));
-        (input, ans.to_string())
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(energy(input, (Point::new(0, 0), Dir4::Right)).into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let (w, h) = (input.width() as i32, input.height() as i32);
-        let r0 = (0..h).map(|r| energy(&input, ((r, 0), 0)));
-        let c0 = (0..w).map(|c| energy(&input, ((0, c), 1)));
-        let r1 = (0..h).map(|r| energy(&input, ((r, w - 1), 2)));
-        let c1 = (0..w).map(|c| energy(&input, ((h - 1, c), 3)));
-        r0.chain(r1).chain(c0).chain(c1).max().unwrap().to_string()
+        let r0 = (0..h).map(|r| energy(input, (Point::new(r, 0), Dir4::Right)));
+        let c0 = (0..w).map(|c| energy(input, (Point::new(0, c), Dir4::Down)));
+        let r1 = (0..h).map(|r| energy(input, (Point::new(r, w - 1), Dir4::Left)));
+        let c1 = (0..w).map(|c| energy(input, (Point::new(h - 1, c), Dir4::Up)));
+        let max = r0.chain(r1).chain(c0).chain(c1).max();
+        Ok(max
+            .ok_or_else(|| SolveError::new("no tiles to energize"))?
+            .into())
+    }
+}
+
+impl<'a> Visualize<'a> for Day16 {
+    fn visualize(input: &Self::Input, backend: &mut dyn Backend) -> io::Result<()> {
+        let mut frame = Frame::from_grid(input, |c| match c {
+            '.' => [16, 16, 16],
+            _ => [160, 160, 200],
+        });
+        let beams = energized(input, (Point::new(0, 0), Dir4::Right));
+        for (dirs, cell) in beams.iter().map(|b| b / 4).dedup_with_count() {
+            let glow = 135 + 30 * dirs as u8;
+            frame.set(cell / input.width(), cell % input.width(), [glow, glow, 0]);
+        }
+        backend.frame(&frame)
     }
 }
 
@@ -78,9 +112,9 @@ mod test_day16 {
 
     #[test]
     fn test_day16_examples() {
-        let input = Day16::parse(EXAMPLE);
-        let (input, part1) = Day16::solve_part1(input);
-        let part2 = Day16::solve_part2(input);
+        let input = Day16::parse(EXAMPLE).unwrap();
+        let part1 = Day16::part1(&input, &()).unwrap();
+        let part2 = Day16::part2(&input, &()).unwrap();
         assert_eq!(part1, "46");
         assert_eq!(part2, "51");
     }
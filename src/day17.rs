@@ -1,38 +1,40 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
+use crate::{
+    day::{Answer, Day, Expected, ParseError, SolveError},
+    trace,
+    util::{search, Grid},
+};
 
-use crate::day::Day;
-
-fn min_heat(grid: &[Vec<i32>], min: i32, max: i32) -> Option<i32> {
-    let (width, height) = (grid[0].len() as i32, grid.len() as i32);
-    let mut heap = BinaryHeap::new();
-    let mut seen = vec![vec![[None; 2]; width as usize]; height as usize];
-    heap.push((Reverse(0), (0, 0, 0)));
-    heap.push((Reverse(0), (0, 0, 1)));
-    seen[0][0] = [Some(0), Some(0)];
-    while let Some((Reverse(heat), (r, c, d))) = heap.pop() {
-        if seen[r as usize][c as usize][d as usize].map_or(false, |h| h > heat) {
-            continue;
-        }
-        if (r, c) == (height - 1, width - 1) {
-            return Some(heat);
-        }
+fn min_heat(grid: &Grid<i32>, min: i32, max: i32) -> Result<i32, SolveError> {
+    if grid.width() == 0 || grid.height() == 0 {
+        return Err(SolveError::new("empty map"));
+    }
+    let (width, height) = (grid.width() as i32, grid.height() as i32);
+    let successors = |&(r, c, d): &(i32, i32, i32)| {
+        let mut next = Vec::new();
         for (dr, dc) in [(d, 1 - d), (-d, d - 1)] {
-            let mut nh = heat;
+            let mut heat = 0;
             for (n, r, c) in (1..max + 1)
                 .map(|n| (n, r + n * dr, c + n * dc))
                 .take_while(|&(_, r, c)| r >= 0 && c >= 0 && r < height && c < width)
             {
-                nh += grid[r as usize][c as usize];
-                if n >= min
-                    && seen[r as usize][c as usize][1 - d as usize].map_or(true, |oh| oh > nh)
-                {
-                    heap.push((Reverse(nh), (r, c, 1 - d)));
-                    seen[r as usize][c as usize][1 - d as usize] = Some(nh);
+                heat += grid[(r as usize, c as usize)];
+                if n >= min {
+                    next.push(((r, c, 1 - d), heat));
                 }
             }
         }
-    }
-    None
+        trace!(Trace, "expand", pos = (r, c), vertical = d == 1);
+        next
+    };
+    let goal = |&(r, c, _): &(i32, i32, i32)| (r, c) == (height - 1, width - 1);
+    // each cell can be left horizontally or vertically
+    let index = |&(r, c, d): &(i32, i32, i32)| ((r * width + c) * 2 + d) as usize;
+    let size = 2 * grid.width() * grid.height();
+    let starts = [(0, 0, 0), (0, 0, 1)];
+    let (_, heat) = search::dijkstra_dense(size, index, starts, successors, goal)
+        .ok_or_else(|| SolveError::new("no path to the bottom right"))?;
+    trace!(Debug, "reached goal", heat = heat);
+    Ok(heat)
 }
 
 pub struct Day17;
@@ -40,24 +42,25 @@ pub struct Day17;
 impl<'a> Day<'a> for Day17 {
     const DAY: usize = 17;
 
-    type Input = Vec<Vec<i32>>;
-    type ProcessedInput = Vec<Vec<i32>>;
+    type Input = Grid<i32>;
+    type Shared = ();
+
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        Grid::parse(input, |c| {
+            c.to_digit(10).map(|d| d as i32).expected("a digit")
+        })
+    }
 
-    fn parse(input: &'a str) -> Self::Input {
-        input
-            .trim()
-            .lines()
-            .map(|line| line.bytes().map(|b| (b - b'0') as i32).collect())
-            .collect()
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = min_heat(&input, 1, 3).unwrap();
-        (input, ans.to_string())
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(min_heat(input, 1, 3)?.into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        min_heat(&input, 4, 10).unwrap().to_string()
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(min_heat(input, 4, 10)?.into())
     }
 }
 
@@ -84,10 +87,14 @@ mod test_day17 {
 
     #[test]
     fn test_day17_examples() {
-        let input = Day17::parse(EXAMPLE);
-        let (input, part1) = Day17::solve_part1(input);
-        let part2 = Day17::solve_part2(input);
+        let input = Day17::parse(EXAMPLE).unwrap();
+        let part1 = Day17::part1(&input, &()).unwrap();
+        let part2 = Day17::part2(&input, &()).unwrap();
         assert_eq!(part1, "102");
         assert_eq!(part2, "94");
+
+        // too narrow to ever go four blocks before turning
+        let input = Day17::parse("111\n111\n111").unwrap();
+        assert!(Day17::part2(&input, &()).is_err());
     }
 }
@@ -1,13 +1,26 @@
-use crate::day::Day;
-
-fn area<I: Iterator<Item = (i64, i64)>>(deltas: I) -> i64 {
-    let (mut x, mut y, mut int) = (0, 0, 0);
-    for (dx, dy) in deltas {
-        int += y * dx - x * dy + dx.abs() + dy.abs();
-        x += dx;
-        y += dy;
-    }
-    int / 2 + 1
+use std::io;
+
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    util::{geometry, math, Bounds, Dir4, GridView, Point, SparseGrid},
+    viz::{Backend, Frame, Visualize},
+};
+
+// the trench and everything inside it
+fn lagoon(vertices: &[(i64, i64)]) -> Result<i64, SolveError> {
+    let inside = geometry::interior_points(vertices);
+    let edge = geometry::boundary_points(vertices);
+    let size = inside.zip(edge).and_then(|(i, e)| i.checked_add(e));
+    size.checked("lagoon size")
+}
+
+fn trench<I: IntoIterator<Item = (Dir4, i64)>>(steps: I) -> Result<Vec<(i64, i64)>, SolveError> {
+    let steps = steps.into_iter().collect::<Vec<_>>();
+    // no corner is further from the start than the plan is long, so once that fits the
+    // steps can only fail to close
+    math::checked_sum(steps.iter().map(|s| s.1)).checked("length of the dig plan")?;
+    geometry::polygon_from_steps(steps)
+        .ok_or_else(|| SolveError::new("dig plan doesn't return to the start"))
 }
 
 pub struct Day18;
@@ -15,38 +28,92 @@ pub struct Day18;
 impl<'a> Day<'a> for Day18 {
     const DAY: usize = 18;
 
-    type Input = Vec<(u8, i64, u8, i64)>;
-    type ProcessedInput = Vec<(u8, i64, u8, i64)>;
+    type Input = Vec<(Dir4, i64, u8, i64)>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .trim()
             .lines()
             .map(|line| {
-                let (dir1, rest) = line.split_once(' ').unwrap();
-                let (n1, color) = rest.split_once(' ').unwrap();
-                let (n2, dir2) = color[2..color.len() - 1].split_at(5);
-                let (dir1, dir2) = (dir1.as_bytes()[0], dir2.as_bytes()[0]);
-                let (n1, n2) = (n1.parse().unwrap(), i64::from_str_radix(n2, 16).unwrap());
-                (dir1, n1, dir2, n2)
+                let (dir1, rest) = line.split_once(' ').expected("direction")?;
+                let (n1, color) = rest.split_once(' ').expected("distance and color")?;
+                let color = color.strip_prefix("(#").and_then(|c| c.strip_suffix(')'));
+                let (n2, dir2) = color
+                    .filter(|c| c.len() == 6 && c.is_ascii())
+                    .expected("six digit color")?
+                    .split_at(5);
+                let dir1 = match dir1.as_bytes() {
+                    &[d @ (b'U' | b'D' | b'L' | b'R')] => Dir4::from_char(d as char).unwrap(),
+                    _ => return Err(ParseError::new("expected U, D, L or R")),
+                };
+                let dir2 = match dir2.as_bytes() {
+                    &[d] if (b'0'..=b'3').contains(&d) => d,
+                    _ => return Err(ParseError::new("expected direction digit 0-3")),
+                };
+                let n1 = n1.parse::<u32>()?;
+                Ok((dir1, n1 as i64, dir2, i64::from_str_radix(n2, 16)?))
             })
             .collect()
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = area(input.iter().copied().map(|(dir, n, _, _)| match dir {
-            b'U' => (0, n),
-            b'D' => (0, -n),
-            b'L' => (-n, 0),
-            b'R' => (n, 0),
-            _ => panic!("unrecognized direction"),
-        }));
-        (input, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        let delta = |n, dir| [(n, 0), (0, -n), (-n, 0), (0, n)][(dir - b'0') as usize];
-        area(input.into_iter().map(|(_, _, dir, n)| delta(n, dir))).to_string()
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let steps = input.iter().map(|&(dir, n, _, _)| (dir, n));
+        Ok(lagoon(&trench(steps)?)?.into())
+    }
+
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let dirs = [Dir4::Right, Dir4::Down, Dir4::Left, Dir4::Up];
+        let steps = input
+            .iter()
+            .map(|&(_, _, dir, n)| (dirs[(dir - b'0') as usize], n));
+        Ok(lagoon(&trench(steps)?)?.into())
+    }
+}
+
+impl<'a> Visualize<'a> for Day18 {
+    fn visualize(input: &Self::Input, backend: &mut dyn Backend) -> io::Result<()> {
+        let mut trench = vec![(Point::new(0, 0), [0; 3])];
+        for &(dir, n, dir2, n2) in input.iter() {
+            let color = ((n2 << 4) + (dir2 - b'0') as i64).to_be_bytes();
+            for _ in 0..n {
+                let (p, _) = trench[trench.len() - 1];
+                trench.push((p.step(dir, 1), [color[5], color[6], color[7]]));
+            }
+        }
+        trench[0].1 = trench[trench.len() - 1].1;
+        let colors = trench.iter().copied().collect::<SparseGrid<_>>();
+        let Bounds { rows, cols } = colors.bounds().unwrap();
+        let pixel = |p: Point| ((p.row - rows.start) as usize, (p.col - cols.start) as usize);
+        let mut frame = Frame::new(cols.len(), rows.len());
+        let draw_trench = |frame: &mut Frame| {
+            for (p, &color) in colors.iter() {
+                let (r, c) = pixel(p);
+                frame.set(r, c, color);
+            }
+        };
+        draw_trench(&mut frame);
+        backend.frame(&frame)?;
+        for row in rows.clone() {
+            let mut crossings = trench
+                .windows(2)
+                .filter(|w| w[0].0.col == w[1].0.col && w[0].0.row.max(w[1].0.row) == row)
+                .map(|w| w[0].0.col)
+                .collect::<Vec<_>>();
+            crossings.sort_unstable();
+            for cs in crossings.chunks_exact(2) {
+                for col in cs[0]..=cs[1] {
+                    let (r, c) = pixel(Point::new(row, col));
+                    frame.set(r, c, [96, 72, 48]);
+                }
+            }
+        }
+        draw_trench(&mut frame);
+        backend.frame(&frame)
     }
 }
 
@@ -54,6 +121,7 @@ impl<'a> Day<'a> for Day18 {
 mod test_day18 {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc! {"
         R 6 (#70c710)
@@ -74,10 +142,90 @@ mod test_day18 {
 
     #[test]
     fn test_day18_examples() {
-        let input = Day18::parse(EXAMPLE);
-        let (input, part1) = Day18::solve_part1(input);
-        let part2 = Day18::solve_part2(input);
+        let input = Day18::parse(EXAMPLE).unwrap();
+        let part1 = Day18::part1(&input, &()).unwrap();
+        let part2 = Day18::part2(&input, &()).unwrap();
         assert_eq!(part1, "62");
         assert_eq!(part2, "952408144115");
+        assert!(Day18::part1(&input[1..].to_vec(), &()).is_err());
+        assert!(Day18::parse("R -6 (#70c710)").is_err());
+
+        let far = "R 1 (#fffff0)\nD 1 (#fffff1)\nL 1 (#fffff2)\nU 1 (#fffff3)";
+        let mut input = Day18::parse(far).unwrap();
+        assert_eq!(Day18::part1(&input, &()), Ok(Answer::from(4)));
+        for step in input.iter_mut() {
+            step.3 = i64::MAX / 2;
+        }
+        let err = Day18::part2(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "length of the dig plan overflowed");
+        for step in input.iter_mut() {
+            step.3 = 1 << 40;
+        }
+        let err = Day18::part2(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "lagoon size overflowed");
+    }
+
+    // an x-monotone rectilinear polygon made of vertical strips (width, bottom, top)
+    fn strips() -> impl Strategy<Value = Vec<(i64, i64, i64)>> {
+        prop::collection::vec((1..4i64, -5..5i64, 1..6i64), 1..6)
+            .prop_map(|strips| {
+                strips
+                    .into_iter()
+                    .map(|(w, b, h)| (w, b, b + h))
+                    .collect::<Vec<_>>()
+            })
+            .prop_filter("strips must overlap", |strips| {
+                strips
+                    .windows(2)
+                    .all(|s| s[0].1.max(s[1].1) < s[0].2.min(s[1].2))
+            })
+    }
+
+    fn vertices(strips: &[(i64, i64, i64)]) -> Vec<(i64, i64)> {
+        let mut xs = vec![0];
+        strips
+            .iter()
+            .for_each(|s| xs.push(xs.last().unwrap() + s.0));
+        let mut vs = vec![(0, strips[0].1)];
+        for (i, &(_, _, t)) in strips.iter().enumerate() {
+            vs.extend([(xs[i], t), (xs[i + 1], t)]);
+        }
+        for (i, &(_, b, _)) in strips.iter().enumerate().rev() {
+            vs.extend([(xs[i + 1], b), (xs[i], b)]);
+        }
+        vs
+    }
+
+    fn brute_area(vs: &[(i64, i64)]) -> i64 {
+        let edges = (0..vs.len()).map(|i| (vs[i], vs[(i + 1) % vs.len()]));
+        let edges = edges.collect::<Vec<_>>();
+        let on_edge = |x, y, ((x0, y0), (x1, y1)): ((i64, i64), (i64, i64))| {
+            x0.min(x1) <= x && x <= x0.max(x1) && y0.min(y1) <= y && y <= y0.max(y1)
+        };
+        let crosses = |x, y, ((x0, y0), (x1, y1)): ((i64, i64), (i64, i64))| {
+            x0 == x1 && x0 > x && y0.min(y1) <= y && y < y0.max(y1)
+        };
+        let mut count = 0;
+        for x in -1..20 {
+            for y in -6..12 {
+                let on_boundary = edges.iter().any(|&e| on_edge(x, y, e));
+                let inside = edges.iter().filter(|&&e| crosses(x, y, e)).count() % 2 == 1;
+                count += (on_boundary || inside) as i64;
+            }
+        }
+        count
+    }
+
+    proptest! {
+        #[test]
+        fn test_day18_lagoon(strips in strips(), start in 0..100usize, reverse: bool) {
+            let mut vs = vertices(&strips);
+            let n = vs.len();
+            vs.rotate_left(start % n);
+            if reverse {
+                vs.reverse();
+            }
+            prop_assert_eq!(lagoon(&vs), Ok(brute_area(&vs)));
+        }
     }
 }
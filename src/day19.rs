@@ -1,6 +1,14 @@
 use std::{array, collections::HashMap};
 
-use crate::day::Day;
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    util::{
+        interval::{Interval, IntervalBox},
+        math, parse,
+    },
+};
+
+const CATEGORIES: [&str; 4] = ["x", "m", "a", "s"];
 
 #[derive(Debug, Default, Clone)]
 pub struct Workflow<'a> {
@@ -10,88 +18,143 @@ pub struct Workflow<'a> {
 
 fn count_accepted<'a>(
     name: &'a str,
-    mut parts: [(u64, u64); 4],
+    parts: IntervalBox<u64, 4>,
+    workflows: &HashMap<&'a str, Workflow<'a>>,
+) -> Result<u64, SolveError> {
+    count_within(name, parts, workflows, 0)
+}
+
+// a part that has been through more workflows than there are must have visited one twice,
+// and since each part always takes the same route it would go round forever
+fn count_within<'a>(
+    name: &'a str,
+    mut parts: IntervalBox<u64, 4>,
     workflows: &HashMap<&'a str, Workflow<'a>>,
-) -> u64 {
-    if name == "R" || (0..4).any(|i| parts[i].1 < parts[i].0) {
-        return 0;
+    depth: usize,
+) -> Result<u64, SolveError> {
+    if name == "R" || parts.is_empty() {
+        return Ok(0);
     }
     if name == "A" {
-        return (0..4).map(|i| parts[i].1 - parts[i].0 + 1).product();
+        return Ok(parts.volume());
+    }
+    if depth > workflows.len() {
+        return Err(SolveError::new(format!(
+            "workflow {} is part of a loop",
+            name
+        )));
     }
     let mut count = 0;
-    let w = workflows.get(name).unwrap();
+    let w = workflows
+        .get(name)
+        .ok_or_else(|| SolveError::new(format!("no workflow named {}", name)))?;
     for (i, val, less, target) in w.filters.iter().copied() {
-        let mut enter = parts;
-        if less {
-            enter[i].1 = enter[i].1.min(val - 1);
-            parts[i].0 = parts[i].0.max(val);
-        } else {
-            enter[i].0 = enter[i].0.max(val + 1);
-            parts[i].1 = parts[i].1.min(val);
-        }
-        count += count_accepted(target, enter, workflows);
+        let enter;
+        (enter, parts) = match less {
+            true => parts.split_at(i, val),
+            false => {
+                let (rest, enter) = parts.split_after(i, val);
+                (enter, rest)
+            }
+        };
+        count += count_within(target, enter, workflows, depth + 1)?;
+    }
+    Ok(count + count_within(w.default, parts, workflows, depth + 1)?)
+}
+
+fn point(part: &[u64; 4]) -> Result<IntervalBox<u64, 4>, SolveError> {
+    let mut axes = [Interval::new(0, 0); 4];
+    for (axis, &rating) in axes.iter_mut().zip(part) {
+        *axis = Interval::single(rating).checked("rating")?;
     }
-    count + count_accepted(w.default, parts, workflows)
+    Ok(IntervalBox::new(axes))
 }
 
 pub struct Day19;
 
 impl<'a> Day<'a> for Day19 {
     const DAY: usize = 19;
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("accepts <x> <m> <a> <s>", "whether a part is accepted"),
+        ("workflow <name>", "print a workflow"),
+    ];
 
     type Input = (HashMap<&'a str, Workflow<'a>>, Vec<[u64; 4]>);
-    type ProcessedInput = HashMap<&'a str, Workflow<'a>>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        let (workflows, parts) = input.trim().split_once("\n\n").unwrap();
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        let mut pars = parse::paragraphs(input);
+        let workflows = pars.next().expected("workflows")?;
+        let parts = pars.next().expected("blank line before parts")?;
         let workflows = workflows
             .lines()
             .map(|line| {
-                let (name, workflow) = line.split_once('{').unwrap();
-                let mut w = Workflow::default();
-                for filter in workflow.split(',') {
-                    if let Some((pred, target)) = filter.split_once(':') {
-                        let less = pred.split_once('<').map(|(id, val)| (id, val, true));
-                        let greater = pred.split_once('>').map(|(id, val)| (id, val, false));
-                        let (id, val, is_less) = less.or(greater).unwrap();
-                        let i = "xmas".bytes().position(|b| b == id.as_bytes()[0]).unwrap();
-                        w.filters.push((i, val.parse().unwrap(), is_less, target));
-                    } else {
-                        w.default = &filter[..filter.len() - 1];
-                        break;
-                    }
+                let (name, workflow) = line.split_once('{').expected("'{' after name")?;
+                let mut filters = workflow.strip_suffix('}').expected("'}'")?.split(',');
+                let mut w = Workflow {
+                    default: filters.next_back().expected("default target")?,
+                    ..Workflow::default()
+                };
+                for filter in filters {
+                    let (pred, target) = filter.split_once(':').expected("':' in rule")?;
+                    let less = pred.split_once('<').map(|(id, val)| (id, val, true));
+                    let greater = pred.split_once('>').map(|(id, val)| (id, val, false));
+                    let (id, val, is_less) = less.or(greater).expected("'<' or '>'")?;
+                    let i = CATEGORIES
+                        .iter()
+                        .position(|&c| c == id)
+                        .expected("category")?;
+                    w.filters.push((i, val.parse()?, is_less, target));
                 }
-                (name, w)
+                Ok((name, w))
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
         let parts = parts
             .lines()
-            .map(|line| {
-                let mut vals = line.split(',').map(|p| {
-                    let s = p.find(|c: char| c.is_ascii_digit()).unwrap();
-                    let l = p[s..]
-                        .find(|c: char| !c.is_ascii_digit())
-                        .unwrap_or(p[s..].len());
-                    p[s..s + l].parse().unwrap()
-                });
-                array::from_fn(|_| vals.next().unwrap())
-            })
-            .collect();
-        (workflows, parts)
+            .map(parse::ints_array)
+            .collect::<Result<_, ParseError>>()?;
+        Ok((workflows, parts))
     }
 
-    fn solve_part1((workflows, parts): Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = parts
-            .iter()
-            .filter(|p| count_accepted("in", array::from_fn(|i| (p[i], p[i])), &workflows) > 0)
-            .flat_map(|p| p.iter())
-            .sum::<u64>();
-        (workflows, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1((workflows, parts): &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let mut ratings = Vec::<u64>::new();
+        for p in parts {
+            if count_accepted("in", point(p)?, workflows)? > 0 {
+                ratings.extend(p);
+            }
+        }
+        Ok(math::checked_sum(ratings).checked("sum of ratings")?.into())
     }
 
-    fn solve_part2(workflows: Self::ProcessedInput) -> String {
-        count_accepted("in", [(1, 4000); 4], &workflows).to_string()
+    fn part2((workflows, _): &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let parts = IntervalBox::new([Interval::new(1, 4001); 4]);
+        Ok(count_accepted("in", parts, workflows)?.into())
+    }
+
+    fn query((workflows, _): &Self::Input, command: &str, args: &[&str]) -> Result<String, String> {
+        match (command, args) {
+            ("accepts", [_, _, _, _]) => {
+                let part = args
+                    .iter()
+                    .map(|n| n.parse::<u64>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| err.to_string())?;
+                let part = array::from_fn(|i| part[i]);
+                let point = point(&part).map_err(|err| err.to_string())?;
+                let count = count_accepted("in", point, workflows);
+                let accepted = count.map_err(|err| err.to_string())? > 0;
+                Ok(if accepted { "accepted" } else { "rejected" }.to_string())
+            }
+            ("workflow", [name]) => workflows
+                .get(name)
+                .map(|w| format!("{:#?}", w))
+                .ok_or_else(|| format!("No workflow named \"{}\"", name)),
+            _ => Err(format!("Unknown command \"{}\"", command)),
+        }
     }
 }
 
@@ -122,10 +185,22 @@ mod test_day19 {
 
     #[test]
     fn test_day19_examples() {
-        let input = Day19::parse(EXAMPLE);
-        let (input, part1) = Day19::solve_part1(input);
-        let part2 = Day19::solve_part2(input);
+        let input = Day19::parse(EXAMPLE).unwrap();
+        let part1 = Day19::part1(&input, &()).unwrap();
+        let part2 = Day19::part2(&input, &()).unwrap();
         assert_eq!(part1, "19114");
         assert_eq!(part2, "167409079868000");
+
+        let input = Day19::parse("in{x<5:ab,R}\nab{in}\n\n{x=1,m=2,a=3,s=4}").unwrap();
+        let err = Day19::part2(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "workflow ab is part of a loop");
+        assert!(Day19::part1(&input, &()).is_err());
+        let query = Day19::query(&input, "accepts", &["9", "1", "1", "1"]);
+        assert_eq!(query, Ok("rejected".to_string()));
+        let max = u64::MAX.to_string();
+        let query = Day19::query(&input, "accepts", &["1", "1", "1", &max]);
+        assert_eq!(query, Err("rating overflowed".to_string()));
+        let input = Day19::parse("in{x<5:ab,A}\n\n{x=1,m=2,a=3,s=4}").unwrap();
+        assert!(Day19::part1(&input, &()).is_err());
     }
 }
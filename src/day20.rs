@@ -1,65 +1,108 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 
-use num::integer;
+use itertools::Itertools;
 
-use crate::day::Day;
+use crate::{
+    day::{Answer, Checked, Day, Expected, ParseError, SolveError},
+    trace,
+    util::{
+        cycle::{self, Cycle},
+        graph::{Graph, GraphBuilder, Interner},
+        math, search, Ignore,
+    },
+};
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Mod {
     #[default]
     Conj,
     Flip(bool),
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct Node<'a> {
-    ins: Vec<&'a str>,
-    outs: Vec<&'a str>,
-    last: bool,
-    ty: Mod,
-}
-
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Circuit<'a> {
-    broadcasts: Vec<&'a str>,
-    nodes: HashMap<&'a str, Node<'a>>,
+    names: Interner<'a>,
+    outs: Graph,
+    ins: Graph,
+    broadcaster: usize,
+    mods: Vec<Mod>,
+    last: Vec<bool>,
 }
 
 impl<'a> Circuit<'a> {
-    fn broadcast(&mut self, target: &'a str) -> ([usize; 2], bool) {
+    fn broadcast(&mut self, target: Option<usize>) -> ([usize; 2], bool) {
         let mut queue = self
-            .broadcasts
+            .outs
+            .neighbours(self.broadcaster)
             .iter()
-            .map(|&b| (b, false))
+            .map(|&(b, _)| (b, false))
             .collect::<VecDeque<_>>();
         let mut pulses = [1, 0];
         let mut hit = false;
         while let Some((n, high)) = queue.pop_front() {
+            trace!(Trace, "pulse", to = self.names.name(n), high = high);
             pulses[high as usize] += 1;
-            let node = self.nodes.get_mut(n).unwrap();
-            let mut pulse = None;
-            match &mut node.ty {
+            let pulse = match &mut self.mods[n] {
                 Mod::Flip(flip) if !high => {
                     *flip = !*flip;
-                    pulse = Some(*flip);
+                    Some(*flip)
                 }
-                Mod::Conj => {
-                    let node = self.nodes.get(n).unwrap();
-                    pulse = Some(!node.ins.iter().all(|&i| self.nodes.get(i).unwrap().last));
-                }
-                _ => (),
-            }
+                Mod::Conj => Some(!self.ins.neighbours(n).iter().all(|&(i, _)| self.last[i])),
+                _ => None,
+            };
             if let Some(high) = pulse {
-                if (n, high) == (target, true) {
+                if high && target == Some(n) {
                     hit = true;
                 }
-                let node = self.nodes.get_mut(n).unwrap();
-                node.last = high;
-                queue.extend(node.outs.iter().map(|&o| (o, high)));
+                self.last[n] = high;
+                queue.extend(self.outs.neighbours(n).iter().map(|&(o, _)| (o, high)));
             }
         }
         (pulses, hit)
     }
+
+    // the loop of states everything upstream of `node` settles into as the button is
+    // pressed, and the presses on which `node` sends a high pulse until the end of the
+    // first time round it
+    fn high_pulses(&self, node: usize) -> HighPulses {
+        let ins = |&n: &usize| self.ins.neighbours(n).iter().map(|&(i, _)| i);
+        let upstream = search::bfs_iter([node], ins)
+            .map(|(n, _)| n)
+            .collect::<Vec<_>>();
+        let state = |c: &Circuit| {
+            let state = upstream.iter().map(|&n| (c.mods[n], c.last[n]));
+            state.collect::<Vec<_>>()
+        };
+        let (mut presses, mut hits) = (0, Vec::new());
+        let press = |c: &mut Circuit| {
+            presses += 1;
+            if c.broadcast(Some(node)).1 {
+                hits.push(presses);
+            }
+        };
+        let cycle = cycle::find_by_key(self, press, state);
+        HighPulses { cycle, hits }
+    }
+}
+
+// press n is made from the state after n - 1 presses
+struct HighPulses {
+    cycle: Cycle,
+    hits: Vec<usize>,
+}
+
+impl HighPulses {
+    fn on(&self, press: usize) -> bool {
+        let press = self.cycle.reduce(press - 1) + 1;
+        self.hits.binary_search(&press).is_ok()
+    }
+
+    // (first press, period) for each high press once the states loop
+    fn residues(&self) -> Vec<(i64, i64)> {
+        let Cycle { prefix, period } = self.cycle;
+        let looped = self.hits.iter().filter(|&&p| p > prefix);
+        looped.map(|&p| (p as i64, period as i64)).collect()
+    }
 }
 
 pub struct Day20;
@@ -68,51 +111,115 @@ impl<'a> Day<'a> for Day20 {
     const DAY: usize = 20;
 
     type Input = Circuit<'a>;
-    type ProcessedInput = Circuit<'a>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        let mut circuit = Circuit::default();
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        let mut builder = GraphBuilder::directed();
+        let broadcaster = builder.node("broadcaster");
+        let mut mods = Vec::new();
         for line in input.trim().lines() {
-            let (input, output) = line.split_once(" -> ").unwrap();
-            let outs = output.split(", ").collect::<Vec<_>>();
-            if input == "broadcaster" {
-                circuit.broadcasts = outs;
-            } else {
-                let (module, input) = input.split_at(1);
-                outs.iter()
-                    .for_each(|out| circuit.nodes.entry(out).or_default().ins.push(input));
-                let node = circuit.nodes.entry(input).or_default();
-                if module == "%" {
-                    node.ty = Mod::Flip(false);
-                }
-                node.outs = outs;
-            }
+            let (input, output) = line.split_once(" -> ").expected("' -> '")?;
+            let (ty, input) = match (input.strip_prefix('%'), input.strip_prefix('&')) {
+                (Some(input), _) => (Mod::Flip(false), input),
+                (_, Some(input)) => (Mod::Conj, input),
+                _ if input == "broadcaster" => (Mod::default(), input),
+                _ => return Err(ParseError::new("expected '%' or '&' module")),
+            };
+            let node = builder.node(input);
+            output
+                .split(", ")
+                .for_each(|out| builder.edge(input, out, ()).ignore());
+            mods.push((node, ty));
         }
-        circuit
+        let (outs, names) = builder.build();
+        let mut circuit = Circuit {
+            ins: outs.reversed(),
+            last: vec![false; outs.len()],
+            mods: vec![Mod::default(); outs.len()],
+            names,
+            outs,
+            broadcaster,
+        };
+        mods.into_iter().for_each(|(n, ty)| circuit.mods[n] = ty);
+        Ok(circuit)
+    }
+
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let mut circuit = input.clone();
         let mut pulses = [0; 2];
         for _ in 0..1000 {
-            let p = circuit.broadcast("").0;
+            let p = circuit.broadcast(None).0;
             (0..2).for_each(|i| pulses[i] += p[i]);
         }
-        let ans = pulses[0] * pulses[1];
-        (input, ans.to_string())
+        Ok(math::checked_product(pulses)
+            .checked("low pulses times high pulses")?
+            .into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        let rx = input.nodes.get("rx").unwrap();
-        assert_eq!(rx.ins.len(), 1);
-        let trigger = input.nodes.get(rx.ins[0]).unwrap();
-        assert_eq!(trigger.ty, Mod::Conj);
-        let mut periods = vec![];
-        for n in trigger.ins.iter().copied() {
-            let mut circuit = input.clone();
-            periods.push((1..).find(|_| circuit.broadcast(n).1).unwrap());
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let rx = input
+            .names
+            .get("rx")
+            .ok_or_else(|| SolveError::new("no rx module"))?;
+        let &[(trigger, _)] = input.ins.neighbours(rx) else {
+            return Err(SolveError::new("expected rx to have one input"));
+        };
+        if input.mods[trigger] != Mod::Conj {
+            return Err(SolveError::new("expected rx to be fed by a conjunction"));
+        }
+        let mut inputs = Vec::new();
+        for &(n, _) in input.ins.neighbours(trigger) {
+            let pulses = input.high_pulses(n);
+            trace!(
+                Debug,
+                "found cycle",
+                node = input.names.name(n),
+                prefix = pulses.cycle.prefix,
+                period = pulses.cycle.period,
+                hits = pulses.hits
+            );
+            if pulses.hits.is_empty() {
+                let name = input.names.name(n);
+                return Err(SolveError::new(format!(
+                    "{} never sends a high pulse",
+                    name
+                )));
+            }
+            inputs.push(pulses);
+        }
+        let trigger = input.names.name(trigger);
+        if inputs.is_empty() {
+            return Err(SolveError::new(format!("{} has no inputs", trigger)));
+        }
+        // until every input is in its loop the presses have to be checked one by one
+        let settled = inputs.iter().map(|p| p.cycle.prefix).max().unwrap_or(0);
+        if let Some(presses) = (1..=settled).find(|&n| inputs.iter().all(|p| p.on(n))) {
+            return Ok(presses.into());
+        }
+        // after that each input sends a high pulse on some residues modulo its period, so
+        // try every way of picking one residue per input
+        let residues = inputs.iter().map(HighPulses::residues);
+        let (mut best, mut overflowed) = (None, false);
+        for picks in residues.multi_cartesian_product() {
+            match math::first_simultaneous(picks.iter().copied()) {
+                Some(presses) => best = Some(best.map_or(presses, |b: i64| b.min(presses))),
+                None => overflowed |= math::crt_solvable(&picks),
+            }
+        }
+        match (best, overflowed) {
+            (_, true) => Err(SolveError::new(
+                "presses until rx gets a low pulse overflowed",
+            )),
+            (Some(presses), false) => Ok(presses.into()),
+            (None, false) => Err(SolveError::new(format!(
+                "the inputs to {} never all send a high pulse on the same press",
+                trigger
+            ))),
         }
-        periods.into_iter().fold(1u64, integer::lcm).to_string()
     }
 }
 
@@ -129,10 +236,38 @@ mod test_day20 {
         &inv -> a
     "};
 
+    const COUNTER: &str = indoc! {"
+        broadcaster -> a
+        %a -> b, c, e
+        %b -> d, c, e
+        %d -> e
+        &c -> v
+        &e -> w
+        &v -> t
+        &w -> t
+        &t -> rx
+    "};
+
     #[test]
     fn test_day20_examples() {
-        let input = Day20::parse(EXAMPLE);
-        let (_, part1) = Day20::solve_part1(input);
+        let input = Day20::parse(EXAMPLE).unwrap();
+        let part1 = Day20::part1(&input, &()).unwrap();
         assert_eq!(part1, "32000000");
+        assert!(Day20::part2(&input, &()).is_err());
+
+        // x only ever sees high pulses from y, so it never sends one on itself
+        let stuck = "broadcaster -> y\n&y -> x\n&x -> inv\n&inv -> rx\n";
+        let input = Day20::parse(stuck).unwrap();
+        let err = Day20::part2(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "x never sends a high pulse");
+
+        // v sends high pulses on presses 3, 7, 11, ... and w on 7, 15, 23, ..., so they
+        // first coincide on press 7 rather than on lcm(3, 7)
+        let input = Day20::parse(COUNTER).unwrap();
+        assert_eq!(Day20::part2(&input, &()).unwrap(), "7");
+        // without b's pulses c goes low on every odd press, so v sends high from press 1
+        let odd = COUNTER.replace("%b -> d, c, e", "%b -> d, e");
+        let input = Day20::parse(&odd).unwrap();
+        assert_eq!(Day20::part2(&input, &()).unwrap(), "7");
     }
 }
@@ -1,37 +1,65 @@
-use std::collections::{HashSet, VecDeque};
-
-use crate::{day::Day, util::LineGrid};
-
-fn frontier(grid: &LineGrid<'_>, limit: usize, tile: bool) -> usize {
-    let start = grid
-        .iter()
-        .find_map(|(r, c, ch)| (ch == 'S').then_some((r as i32, c as i32)))
-        .unwrap();
-    let mut seen = [HashSet::new(), HashSet::new()];
-    let mut queue = VecDeque::new();
-    queue.push_back((0, start));
-    seen[0].insert(start);
-    while let Some((steps, pos)) = queue.pop_front() {
-        if steps >= limit {
-            break;
-        }
-        for dp in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
-            let p = (pos.0 + dp.0, pos.1 + dp.1);
-            let eff = if tile {
-                let len = grid.width() as i32;
-                (p.0.rem_euclid(len), p.1.rem_euclid(len))
-            } else {
-                p
-            };
-            if !seen[(steps + 1) % 2].contains(&p)
-                && matches!(grid.try_get(eff.0, eff.1), Some(c) if c != '#')
-            {
-                seen[(steps + 1) % 2].insert(p);
-                queue.push_back((steps + 1, p));
-            }
+use std::io;
+
+use crate::{
+    day::{Answer, Checked, Day, ParseError, SolveError},
+    trace,
+    util::{hash::FastHashSet, poly::Polynomial, search, GridView, LineGrid, Point, Tiled},
+    viz::{Backend, Frame, Visualize},
+};
+
+fn start(grid: &LineGrid<'_>) -> Result<Point, SolveError> {
+    grid.points()
+        .find(|&p| grid.at(p) == Some('S'))
+        .ok_or_else(|| SolveError::new("no start tile"))
+}
+
+fn reachable<G: GridView<Cell = char>>(grid: G, start: Point, limit: usize) -> FastHashSet<Point> {
+    let steps = |&p: &Point| {
+        p.neighbours4()
+            .filter(|&q| matches!(grid.at(q), Some(ch) if ch != '#'))
+    };
+    // the grid is bipartite so a tile reached in fewer steps can be revisited every other step
+    search::bfs_iter([start], steps)
+        .take_while(|&(_, dist)| dist <= limit)
+        .filter(|&(_, dist)| dist % 2 == limit % 2)
+        .map(|(pos, _)| pos)
+        .collect()
+}
+
+fn frontier(grid: &LineGrid<'_>, limit: usize, tile: bool) -> Result<usize, SolveError> {
+    let start = start(grid)?;
+    Ok(match tile {
+        true => reachable(Tiled::new(grid), start, limit).len(),
+        false => reachable(grid, start, limit).len(),
+    })
+}
+
+// the garden repeats every width columns and every height rows, so each common multiple of
+// the two grows the frontier by whole tiles, and quadratically once the differences settle
+fn tiled_frontier(grid: &LineGrid<'_>, limit: usize) -> Result<i64, SolveError> {
+    if grid.width() == 0 || grid.height() == 0 {
+        return Err(SolveError::new("empty garden"));
+    }
+    let period = num::integer::lcm(grid.width(), grid.height());
+    let s = limit % period;
+    let f = |n| frontier(grid, s + n * period, true);
+    let mut x = vec![f(0)?, f(1)?, f(2)?];
+    for i in 0.. {
+        x.push(f(i + 3)?);
+        if let Some(poly) = Polynomial::fit(&x[i..]) {
+            trace!(
+                Debug,
+                "differences stabilised",
+                x = x,
+                degree = poly.degree()
+            );
+            let j = (limit - s) / period;
+            return poly
+                .checked_eval(j as i64 - i as i64)
+                .checked("tiled frontier");
         }
     }
-    seen[limit % 2].len()
+    unreachable!()
 }
 
 pub struct Day21Generic<const N1: usize, const N2: usize>;
@@ -41,35 +69,40 @@ impl<'a, const N1: usize, const N2: usize> Day<'a> for Day21Generic<N1, N2> {
     const DAY: usize = 21;
 
     type Input = LineGrid<'a>;
-    type ProcessedInput = LineGrid<'a>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        LineGrid::new(input)
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        LineGrid::parse(input)
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = frontier(&input, N1, false);
-        (input, ans.to_string())
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        let s = N2 % input.width();
-        let f = |n| frontier(&input, s + n * input.width(), true);
-        let mut x = vec![f(0), f(1), f(2)];
-        let mut dx = vec![x[1] - x[0], x[2] - x[1]];
-        let mut ddx = vec![dx[1] - dx[0]];
-        for i in 0.. {
-            x.push(f(i + 3));
-            dx.push(x[i + 3] - x[i + 2]);
-            ddx.push(dx[i + 2] - dx[i + 1]);
-            if ddx[i + 1] == ddx[i] {
-                let j = ((N2 - s) / input.width()) as i64;
-                let (i, xi, dxi, ddxi) = (i as i64, x[i] as i64, dx[i] as i64, ddx[i] as i64);
-                let ext = xi + (j - i) * dxi + (j - i) * (j - i - 1) * ddxi / 2;
-                return ext.to_string();
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(frontier(input, N1, false)?.into())
+    }
+
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok(tiled_frontier(input, N2)?.into())
+    }
+}
+
+impl<'a, const N1: usize, const N2: usize> Visualize<'a> for Day21Generic<N1, N2> {
+    fn visualize(input: &Self::Input, backend: &mut dyn Backend) -> io::Result<()> {
+        let garden = Frame::from_grid(input, |c| match c {
+            '#' => [48, 64, 48],
+            _ => [16, 16, 16],
+        });
+        let start = start(input).map_err(io::Error::other)?;
+        for steps in 0..=N1 {
+            let mut frame = garden.clone();
+            for p in reachable(input, start, steps) {
+                frame.set(p.row as usize, p.col as usize, [96, 224, 96]);
             }
+            backend.frame(&frame)?;
         }
-        unreachable!()
+        Ok(())
     }
 }
 
@@ -77,6 +110,8 @@ impl<'a, const N1: usize, const N2: usize> Day<'a> for Day21Generic<N1, N2> {
 mod test_day21 {
     use super::*;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc! {"
         ...........
@@ -94,10 +129,48 @@ mod test_day21 {
 
     #[test]
     fn test_day21_examples() {
-        let input = Day21Generic::<6, 5000>::parse(EXAMPLE);
-        let (input, part1) = Day21Generic::<6, 5000>::solve_part1(input);
-        let part2 = Day21Generic::<6, 5000>::solve_part2(input);
+        let input = Day21Generic::<6, 5000>::parse(EXAMPLE).unwrap();
+        let part1 = Day21Generic::<6, 5000>::part1(&input, &()).unwrap();
+        let part2 = Day21Generic::<6, 5000>::part2(&input, &()).unwrap();
         assert_eq!(part1, "16");
         assert_eq!(part2, "16733044");
+
+        let input = LineGrid::new("...\n.#.\n...");
+        assert!(Day21::part1(&input, &()).is_err());
+        assert!(Day21::part2(&LineGrid::new(""), &()).is_err());
+    }
+
+    // maps with a clear border and clear row and column through the start
+    fn garden() -> impl Strategy<Value = String> {
+        (2..4usize, 2..4usize).prop_flat_map(|(q, r)| {
+            let (h, w) = (2 * q + 1, 2 * r + 1);
+            prop::collection::vec(prop::bool::weighted(0.2), h * w).prop_map(move |rocks| {
+                (0..h)
+                    .map(|i| {
+                        (0..w)
+                            .map(|j| match (i, j) {
+                                _ if (i, j) == (q, r) => 'S',
+                                _ if i == q || j == r || i % (h - 1) == 0 || j % (w - 1) == 0 => {
+                                    '.'
+                                }
+                                _ if rocks[i * w + j] => '#',
+                                _ => '.',
+                            })
+                            .collect::<String>()
+                    })
+                    .join("\n")
+            })
+        })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn test_day21_tiled_frontier(garden in garden(), tiles in 0..12usize, extra in 0..11usize) {
+            let grid = LineGrid::new(&garden);
+            let limit = tiles * grid.width() + extra % grid.width();
+            let brute = frontier(&grid, limit, true).unwrap() as i64;
+            prop_assert_eq!(tiled_frontier(&grid, limit), Ok(brute));
+        }
     }
 }
@@ -1,16 +1,21 @@
-use std::{
-    array,
-    collections::{HashMap, HashSet, VecDeque},
-};
+use std::collections::VecDeque;
 
 use itertools::iproduct;
 
-use crate::day::Day;
+use crate::{
+    day::{Answer, Checked, Day, ParseError, SolveError},
+    scan,
+    util::{
+        graph::Graph,
+        hash::{FastHashMap, FastHashSet},
+        math,
+    },
+};
 
-#[derive(Default)]
+// edges from each brick to the bricks resting on it, and back
 pub struct Support {
-    up: HashMap<usize, HashSet<usize>>,
-    down: HashMap<usize, HashSet<usize>>,
+    up: Graph,
+    down: Graph,
 }
 
 fn range(b: ([usize; 3], [usize; 3])) -> impl Iterator<Item = [usize; 3]> {
@@ -21,12 +26,12 @@ fn lower(b: ([usize; 3], [usize; 3]), d: usize) -> ([usize; 3], [usize; 3]) {
     ([b.0[0], b.0[1], b.0[2] - d], [b.1[0], b.1[1], b.1[2] - d])
 }
 
-fn settle_bricks(bricks: &mut [([usize; 3], [usize; 3])]) -> HashMap<[usize; 3], usize> {
+fn settle_bricks(bricks: &mut [([usize; 3], [usize; 3])]) -> FastHashMap<[usize; 3], usize> {
     let mut space = bricks
         .iter()
         .enumerate()
         .flat_map(|(i, &b)| range(b).map(move |p| (p, i)))
-        .collect::<HashMap<_, _>>();
+        .collect::<FastHashMap<_, _>>();
     let mut moved = true;
     while moved {
         moved = false;
@@ -49,36 +54,45 @@ fn settle_bricks(bricks: &mut [([usize; 3], [usize; 3])]) -> HashMap<[usize; 3],
     space
 }
 
-fn get_support(bricks: &[([usize; 3], [usize; 3])], space: &HashMap<[usize; 3], usize>) -> Support {
-    let mut support = Support::default();
+fn get_support(
+    bricks: &[([usize; 3], [usize; 3])],
+    space: &FastHashMap<[usize; 3], usize>,
+) -> Support {
+    let mut up = Graph::directed(bricks.len());
     for (i, &b) in bricks.iter().enumerate() {
         let mut scan = lower(b, 1);
         scan.1[2] = scan.0[2];
-        for p in range(scan) {
-            if let Some(j) = space.get(&p).copied() {
-                support.up.entry(j).or_default().insert(i);
-                support.down.entry(i).or_default().insert(j);
-            }
-        }
+        let below = range(scan).filter_map(|p| space.get(&p).copied());
+        below
+            .collect::<FastHashSet<_>>()
+            .into_iter()
+            .for_each(|j| up.add_edge(j, i, ()));
+    }
+    Support {
+        down: up.reversed(),
+        up,
     }
-    support
 }
 
 fn count_dropped(brick: usize, support: &Support) -> usize {
-    let (mut dropped, mut queue) = (HashSet::new(), VecDeque::new());
+    let (mut dropped, mut queue) = (FastHashSet::default(), VecDeque::new());
     queue.push_back(brick);
     while let Some(i) = queue.pop_front() {
         dropped.insert(i);
-        if let Some(rs) = support.up.get(&i) {
-            queue.extend(rs.iter().filter(|r| {
-                support
-                    .down
-                    .get(r)
-                    .unwrap()
-                    .iter()
-                    .all(|s| dropped.contains(s))
-            }));
-        }
+        queue.extend(
+            support
+                .up
+                .neighbours(i)
+                .iter()
+                .map(|&(r, _)| r)
+                .filter(|&r| {
+                    support
+                        .down
+                        .neighbours(r)
+                        .iter()
+                        .all(|(s, _)| dropped.contains(s))
+                }),
+        );
     }
     dropped.len() - 1
 }
@@ -89,34 +103,44 @@ impl<'a> Day<'a> for Day22 {
     const DAY: usize = 22;
 
     type Input = Vec<([usize; 3], [usize; 3])>;
-    type ProcessedInput = Vec<usize>;
+    type Shared = Vec<usize>;
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .lines()
             .map(|line| {
-                let (s, e) = line.split_once('~').unwrap();
-                let split = |s: &'a str| {
-                    let mut it = s.split(',').map(|n| n.parse().unwrap());
-                    move |_| it.next().unwrap()
-                };
-                (array::from_fn(split(s)), array::from_fn(split(e)))
+                let (x0, y0, z0, x1, y1, z1) = scan!(
+                    line,
+                    "{},{},{}~{},{},{}" => usize, usize, usize, usize, usize, usize
+                )?;
+                if z0 == 0 || x0 > x1 || y0 > y1 || z0 > z1 {
+                    return Err(ParseError::new(format!(
+                        "expected corners ordered low to high, at z 1 or above, in \"{}\"",
+                        line
+                    )));
+                }
+                Ok(([x0, y0, z0], [x1, y1, z1]))
             })
             .collect()
     }
 
-    fn solve_part1(mut bricks: Self::Input) -> (Self::ProcessedInput, String) {
+    // how many other bricks fall without each one, once they've all settled
+    fn prepare(input: &Self::Input) -> Result<Self::Shared, SolveError> {
+        let mut bricks = input.clone();
         let space = settle_bricks(&mut bricks);
         let support = get_support(&bricks, &space);
-        let counts = (0..bricks.len())
+        Ok((0..bricks.len())
             .map(|i| count_dropped(i, &support))
-            .collect::<Vec<_>>();
-        let ans = counts.iter().filter(|n| **n == 0).count();
-        (counts, ans.to_string())
+            .collect())
     }
 
-    fn solve_part2(counts: Self::ProcessedInput) -> String {
-        counts.into_iter().sum::<usize>().to_string()
+    fn part1(_: &Self::Input, counts: &Self::Shared) -> Result<Answer, SolveError> {
+        Ok(counts.iter().filter(|n| **n == 0).count().into())
+    }
+
+    fn part2(_: &Self::Input, counts: &Self::Shared) -> Result<Answer, SolveError> {
+        let sum = math::checked_sum(counts.iter().copied());
+        Ok(sum.checked("sum of falling bricks")?.into())
     }
 }
 
@@ -137,10 +161,15 @@ mod test_day22 {
 
     #[test]
     fn test_day22_examples() {
-        let input = Day22::parse(EXAMPLE);
-        let (input, part1) = Day22::solve_part1(input);
-        let part2 = Day22::solve_part2(input);
+        let input = Day22::parse(EXAMPLE).unwrap();
+        let shared = Day22::prepare(&input).unwrap();
+        let part1 = Day22::part1(&input, &shared).unwrap();
+        let part2 = Day22::part2(&input, &shared).unwrap();
         assert_eq!(part1, "5");
         assert_eq!(part2, "7");
+
+        assert!(Day22::parse("1,0,0~1,2,0").is_err());
+        assert!(Day22::parse("1,1,98~1,1,9").is_err());
+        assert!(Day22::parse("2,0,1~1,0,1").is_err());
     }
 }
@@ -1,11 +1,25 @@
-use std::collections::HashMap;
+use std::{
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+    thread,
+};
 
-use crate::{day::Day, util::LineGrid};
+use crate::{
+    day::{self, Answer, Day, ParseError, SolveError},
+    trace,
+    util::{
+        bitset::{BitSet, Bits, GrowBitSet},
+        graph::Graph,
+        LineGrid,
+    },
+};
 
-fn graph_of(grid: &LineGrid<'_>, key: bool) -> Vec<Vec<(usize, u32)>> {
+fn graph_of(grid: &LineGrid<'_>, key: bool) -> Graph<u32> {
     let mut nodes = vec![(0, 1), (grid.height() as i32 - 1, grid.width() as i32 - 2)];
-    let mut graph = vec![Vec::new(); 2];
-    let mut seen = 1u64;
+    let mut graph = match key {
+        true => Graph::undirected(2),
+        false => Graph::directed(2),
+    };
+    let mut seen = GrowBitSet::from_iter([0]);
     let mut queue = vec![((0, 1), (0, 1), 0, 0)];
     while let Some((curr, prev, root, steps)) = queue.pop() {
         let mut nxts = Vec::new();
@@ -25,43 +39,157 @@ fn graph_of(grid: &LineGrid<'_>, key: bool) -> Vec<Vec<(usize, u32)>> {
         } else {
             let node = nodes.iter().position(|&m| m == curr).unwrap_or_else(|| {
                 nodes.push(curr);
-                graph.push(Vec::new());
-                nodes.len() - 1
+                graph.add_node()
             });
-            graph[root].push((node, steps));
-            if key {
-                graph[node].push((root, steps));
+            // without slopes each corridor is walked from both ends
+            if !graph.neighbours(root).contains(&(node, steps)) {
+                graph.add_edge(root, node, steps);
             }
-            if seen & (1 << node) == 0 {
-                seen |= 1 << node;
+            if seen.insert(node) {
                 queue.extend(nxts.into_iter().map(|(nxt, s)| (nxt, curr, node, s)));
             }
         }
     }
+    trace!(Debug, "built graph", nodes = graph.len(), slopes = !key);
     graph
 }
 
-fn max_path(
-    graph: &[Vec<(usize, u32)>],
-    curr: usize,
-    path: u64,
-    memo: &mut HashMap<(usize, u64), Option<u32>>,
-) -> Option<u32> {
-    if curr == 1 {
-        return Some(0);
+// a partial path: where it ends, the nodes on it, and its length
+#[derive(Clone)]
+struct Branch<S> {
+    node: usize,
+    path: S,
+    len: u32,
+}
+
+struct Longest<'g> {
+    graph: &'g Graph<u32>,
+    // each node's edges, lightest first so that the heaviest comes off the stack first
+    edges: Vec<Vec<(usize, u32)>>,
+    best: AtomicU32,
+    states: AtomicUsize,
+}
+
+impl<'g> Longest<'g> {
+    fn new(graph: &'g Graph<u32>) -> Self {
+        let edges = (0..graph.len())
+            .map(|n| {
+                let mut edges = graph.neighbours(n).to_vec();
+                edges.sort_unstable_by_key(|&(_, w)| w);
+                edges
+            })
+            .collect();
+        Longest {
+            graph,
+            edges,
+            best: AtomicU32::new(0),
+            states: AtomicUsize::new(0),
+        }
+    }
+
+    fn start<S: Bits + Default>(&self) -> Branch<S> {
+        Branch {
+            node: 0,
+            path: S::default().with(0),
+            len: 0,
+        }
+    }
+
+    // the most a branch could still add, which is at most the heaviest way into each
+    // node it can still reach, or None if it can't reach the end at all
+    fn bound<S: Bits>(&self, branch: &Branch<S>) -> Option<u32> {
+        let (mut seen, mut stack) = (branch.path.clone(), vec![branch.node]);
+        let mut entry = vec![0; self.graph.len()];
+        while let Some(n) = stack.pop() {
+            for &(m, w) in self.graph.neighbours(n) {
+                if !branch.path.contains(m) {
+                    entry[m] = entry[m].max(w);
+                    if seen.insert(m) {
+                        stack.push(m);
+                    }
+                }
+            }
+        }
+        seen.contains(1).then(|| entry.into_iter().sum())
+    }
+
+    // None once the branch has reached the end or can't beat the best so far
+    fn extend<'b, S: Bits>(
+        &'b self,
+        branch: &'b Branch<S>,
+    ) -> Option<impl 'b + Iterator<Item = Branch<S>>> {
+        if branch.node == 1 {
+            self.best.fetch_max(branch.len, Ordering::Relaxed);
+            return None;
+        }
+        let left = self.bound(branch)?;
+        if branch.len + left <= self.best.load(Ordering::Relaxed) {
+            return None;
+        }
+        let nexts = self.edges[branch.node].iter();
+        Some(
+            nexts
+                .filter(|&&(nxt, _)| !branch.path.contains(nxt))
+                .map(|&(nxt, w)| Branch {
+                    node: nxt,
+                    path: branch.path.with(nxt),
+                    len: branch.len + w,
+                }),
+        )
+    }
+
+    // depth first, without recursion
+    fn search<S: Bits>(&self, branch: Branch<S>) {
+        let (mut stack, mut states) = (vec![branch], 0);
+        while let Some(branch) = stack.pop() {
+            states += 1;
+            if let Some(nexts) = self.extend(&branch) {
+                stack.extend(nexts);
+            }
+        }
+        self.states.fetch_add(states, Ordering::Relaxed);
     }
-    memo.get(&(curr, path)).copied().unwrap_or_else(|| {
-        let steps = graph[curr]
+}
+
+// branch and bound over simple paths from the start to the end, with the first few
+// steps split into enough branches to share between the threads
+fn longest<S: Bits + Default + Send + Sync>(graph: &Graph<u32>, threads: usize) -> Option<u32> {
+    let search = Longest::new(graph);
+    let mut branches = vec![search.start::<S>()];
+    while threads > 1 && !branches.is_empty() && branches.len() < 8 * threads {
+        branches = branches
             .iter()
-            .filter(|(nxt, _)| path & (1 << nxt) == 0)
-            .filter_map(|&(nxt, steps)| {
-                max_path(graph, nxt, path | (1 << nxt), memo).map(|l| l + steps)
-            })
-            .fold(0, u32::max);
-        let ans = (steps > 0).then_some(steps);
-        memo.insert((curr, path), ans);
-        ans
-    })
+            .flat_map(|b| search.extend(b).into_iter().flatten())
+            .collect();
+    }
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..threads.min(branches.len()) {
+            scope.spawn(|| {
+                while let Some(b) = branches.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    search.search(b.clone());
+                }
+            });
+        }
+    });
+    trace!(
+        Debug,
+        "searched",
+        states = search.states.into_inner(),
+        branches = branches.len(),
+        threads = threads
+    );
+    Some(search.best.into_inner()).filter(|&best| best > 0)
+}
+
+// the smallest set that fits every junction
+fn longest_path(graph: &Graph<u32>, threads: usize) -> Option<u32> {
+    match graph.len() {
+        0..=64 => longest::<BitSet<1>>(graph, threads),
+        65..=128 => longest::<BitSet<2>>(graph, threads),
+        129..=256 => longest::<BitSet<4>>(graph, threads),
+        _ => longest::<GrowBitSet>(graph, threads),
+    }
 }
 
 pub struct Day23;
@@ -70,21 +198,28 @@ impl<'a> Day<'a> for Day23 {
     const DAY: usize = 23;
 
     type Input = LineGrid<'a>;
-    type ProcessedInput = LineGrid<'a>;
+    type Shared = ();
+
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        LineGrid::parse(input)
+    }
 
-    fn parse(input: &'a str) -> Self::Input {
-        LineGrid::new(input)
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
-        let ans = max_path(&graph_of(&input, false), 0, 1, &mut HashMap::new()).unwrap();
-        (input, ans.to_string())
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let steps = longest_path(&graph_of(input, false), day::threads());
+        Ok(steps
+            .ok_or_else(|| SolveError::new("no path to the end"))?
+            .into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        max_path(&graph_of(&input, true), 0, 1, &mut HashMap::new())
-            .unwrap()
-            .to_string()
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let steps = longest_path(&graph_of(input, true), day::threads());
+        Ok(steps
+            .ok_or_else(|| SolveError::new("no path to the end"))?
+            .into())
     }
 }
 
@@ -121,10 +256,26 @@ mod test_day23 {
 
     #[test]
     fn test_day23_examples() {
-        let input = Day23::parse(EXAMPLE);
-        let (input, part1) = Day23::solve_part1(input);
-        let part2 = Day23::solve_part2(input);
+        let input = Day23::parse(EXAMPLE).unwrap();
+        let part1 = Day23::part1(&input, &()).unwrap();
+        let part2 = Day23::part2(&input, &()).unwrap();
         assert_eq!(part1, "94");
         assert_eq!(part2, "154");
+        let graph = graph_of(&input, true);
+        assert_eq!(longest_path(&graph, 1), Some(154));
+        assert_eq!(longest::<GrowBitSet>(&graph, 4), Some(154));
+    }
+
+    #[test]
+    fn test_day23_many_junctions() {
+        // a chain of 70 junctions from 0 to 1, with long dead ends off 30 of them
+        let chain = [0].into_iter().chain(2..70).chain([1]).collect::<Vec<_>>();
+        let mut graph = Graph::undirected(100);
+        for (w, i) in chain.windows(2).zip(1..) {
+            graph.add_edge(w[0], w[1], i);
+        }
+        (70..100).for_each(|n| graph.add_edge(chain[n - 65], n, 1000));
+        assert_eq!(longest_path(&graph, 1), Some((1..70).sum()));
+        assert_eq!(longest_path(&graph, 3), Some((1..70).sum()));
     }
 }
@@ -1,26 +1,10 @@
-use std::array;
+use itertools::Itertools;
+use num::{BigInt, Zero};
 
-use crate::day::Day;
-
-fn gauss<const N: usize, const M: usize>(mat: &mut [[f64; N]; M]) {
-    let (mut pr, mut pc) = (0, 0);
-    while pr < M && pc < N {
-        let i_max = (pr..M).max_by_key(|&i| mat[i][pc].abs() as i64).unwrap();
-        if mat[i_max][pc].abs() < 0.000001 {
-            pc += 1;
-            continue;
-        }
-        mat.swap(pr, i_max);
-        for i in pr + 1..M {
-            let f = mat[i][pc] / mat[pr][pc];
-            mat[i][pc] = 0.0;
-            for j in pc + 1..N {
-                mat[i][j] -= mat[pr][j] * f;
-            }
-        }
-        (pr, pc) = (pr + 1, pc + 1);
-    }
-}
+use crate::{
+    day::{Answer, Day, Expected, ParseError, SolveError},
+    util::linalg::{self, Matrix},
+};
 
 pub struct Day24Generic<const MIN: i64, const MAX: i64>;
 pub type Day24 = Day24Generic<200000000000000, 400000000000000>;
@@ -28,27 +12,33 @@ pub type Day24 = Day24Generic<200000000000000, 400000000000000>;
 impl<'a, const MIN: i64, const MAX: i64> Day<'a> for Day24Generic<MIN, MAX> {
     const DAY: usize = 24;
 
-    type Input = Vec<([f64; 3], [f64; 3])>;
-    type ProcessedInput = Vec<([f64; 3], [f64; 3])>;
+    type Input = Vec<([i64; 3], [i64; 3])>;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
         input
             .lines()
             .map(|line| {
-                let (pos, vel) = line.split_once(" @ ").unwrap();
-                let split = |s: &'a str| {
-                    let mut it = s.split(", ").map(|n| n.trim().parse().unwrap());
-                    move |_| it.next().unwrap()
+                let (pos, vel) = line.split_once(" @ ").expected("' @ '")?;
+                let split = |s: &'a str| -> Result<[i64; 3], ParseError> {
+                    let it = s.split(", ").map(|n| n.trim().parse());
+                    let it = it.collect::<Result<Vec<_>, _>>()?;
+                    it.try_into().ok().expected("three coordinates")
                 };
-                (array::from_fn(split(pos)), array::from_fn(split(vel)))
+                Ok((split(pos)?, split(vel)?))
             })
             .collect()
     }
 
-    fn solve_part1(input: Self::Input) -> (Self::ProcessedInput, String) {
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
         let mut ans = 0;
-        for (i, ([x0, y0, _], [dx0, dy0, _])) in input.iter().copied().enumerate() {
-            for ([x1, y1, _], [dx1, dy1, _]) in input.iter().copied().take(i) {
+        let float = |(p, v): ([i64; 3], [i64; 3])| (p.map(|x| x as f64), v.map(|x| x as f64));
+        for (i, ([x0, y0, _], [dx0, dy0, _])) in input.iter().copied().map(float).enumerate() {
+            for ([x1, y1, _], [dx1, dy1, _]) in input.iter().copied().map(float).take(i) {
                 let disc = dx0 * dy1 - dx1 * dy0;
                 if disc == 0.0 {
                     continue;
@@ -63,32 +53,75 @@ impl<'a, const MIN: i64, const MAX: i64> Day<'a> for Day24Generic<MIN, MAX> {
                 ans += (min <= x && x <= max && min <= y && y <= max) as usize;
             }
         }
-        (input, ans.to_string())
+        Ok(ans.into())
     }
 
-    fn solve_part2(input: Self::ProcessedInput) -> String {
-        let eqn = |i| {
-            let ([x0, y0, z0], [dx0, dy0, dz0]) = input[0];
-            let ([x1, y1, z1], [dx1, dy1, dz1]) = input[i];
-            let rx1 = (y0 * dz0 - z0 * dy0) - (y1 * dz1 - z1 * dy1);
-            let ry1 = (z0 * dx0 - x0 * dz0) - (z1 * dx1 - x1 * dz1);
-            let rz1 = (x0 * dy0 - y0 * dx0) - (x1 * dy1 - y1 * dx1);
+    fn part2(input: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        if input.len() < 3 {
+            return Err(SolveError::new("need at least three hailstones"));
+        }
+        let zero = BigInt::zero;
+        let big = |(p, v): &([i64; 3], [i64; 3])| (p.map(BigInt::from), v.map(BigInt::from));
+        // the coordinates are around 1e14, so their products only fit in a BigInt
+        let eqn = |a, b| {
+            let ([x0, y0, z0], [dx0, dy0, dz0]) = big(a);
+            let ([x1, y1, z1], [dx1, dy1, dz1]) = big(b);
+            let rx1 = (&y0 * &dz0 - &z0 * &dy0) - (&y1 * &dz1 - &z1 * &dy1);
+            let ry1 = (&z0 * &dx0 - &x0 * &dz0) - (&z1 * &dx1 - &x1 * &dz1);
+            let rz1 = (&x0 * &dy0 - &y0 * &dx0) - (&x1 * &dy1 - &y1 * &dx1);
             [
-                [0.0, dz0 - dz1, dy1 - dy0, 0.0, z1 - z0, y0 - y1, rx1],
-                [dz1 - dz0, 0.0, dx0 - dx1, z0 - z1, 0.0, x1 - x0, ry1],
-                [dy0 - dy1, dx1 - dx0, 0.0, y1 - y0, x0 - x1, 0.0, rz1],
+                (
+                    vec![
+                        zero(),
+                        &dz0 - &dz1,
+                        &dy1 - &dy0,
+                        zero(),
+                        &z1 - &z0,
+                        &y0 - &y1,
+                    ],
+                    rx1,
+                ),
+                (
+                    vec![
+                        &dz1 - &dz0,
+                        zero(),
+                        &dx0 - &dx1,
+                        &z0 - &z1,
+                        zero(),
+                        &x1 - &x0,
+                    ],
+                    ry1,
+                ),
+                (
+                    vec![
+                        &dy0 - &dy1,
+                        &dx1 - &dx0,
+                        zero(),
+                        &y1 - &y0,
+                        &x0 - &x1,
+                        zero(),
+                    ],
+                    rz1,
+                ),
             ]
         };
-        let [e1, e2, e3] = eqn(1);
-        let [e4, e5, e6] = eqn(2);
-        let mut aug = [e1, e2, e3, e4, e5, e6];
-        gauss(&mut aug);
-        let mut soln = [0.0; 6];
-        for i in (0..6).rev() {
-            soln[i] = (aug[i][6] - (i..6).map(|j| aug[i][j] * soln[j]).sum::<f64>()) / aug[i][i];
+        // parallel or otherwise degenerate hailstones leave the rock underdetermined, so
+        // keep trying triples until one pins it down
+        let soln = input
+            .iter()
+            .tuple_combinations()
+            .find_map(|(a, b, c)| {
+                let (mat, rhs): (Vec<_>, Vec<_>) = eqn(a, b).into_iter().chain(eqn(a, c)).unzip();
+                let mat = Matrix::from_rows(mat);
+                let rhs = rhs.into_iter().map(linalg::rational).collect::<Vec<_>>();
+                (mat.rank() == 6).then(|| mat.solve(&rhs)).flatten()
+            })
+            .ok_or_else(|| SolveError::new("no three hailstones fix a unique rock trajectory"))?;
+        let sum = &soln[0] + &soln[1] + &soln[2];
+        match sum.is_integer() {
+            true => Ok(sum.to_integer().into()),
+            false => Err(SolveError::new("rock starts at a fractional position")),
         }
-        let [x, y, z] = array::from_fn(|i| soln[i].round() as i64);
-        (x + y + z).to_string()
     }
 }
 
@@ -107,10 +140,18 @@ mod test_day24 {
 
     #[test]
     fn test_day24_examples() {
-        let input = Day24Generic::<7, 27>::parse(EXAMPLE);
-        let (input, part1) = Day24Generic::<7, 27>::solve_part1(input);
-        let part2 = Day24::solve_part2(input);
+        let input = Day24Generic::<7, 27>::parse(EXAMPLE).unwrap();
+        let part1 = Day24Generic::<7, 27>::part1(&input, &()).unwrap();
+        let part2 = Day24::part2(&input, &()).unwrap();
         assert_eq!(part1, "2");
         assert_eq!(part2, "47");
+
+        // a hailstone parallel to the first leaves the first triple degenerate
+        let parallel = format!("{}18, 13, 34 @ -2, 1, -2\n", EXAMPLE);
+        let mut input = Day24::parse(&parallel).unwrap();
+        input.rotate_right(1);
+        input.swap(0, 1);
+        assert_eq!(Day24::part2(&input, &()).unwrap(), "47");
+        assert!(Day24::part2(&input[..2].to_vec(), &()).is_err());
     }
 }
@@ -1,90 +1,47 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-
-use itertools::Itertools;
-
-use crate::{day::Day, util::Ignore};
-
-fn count_edge_occurences<'a>(
-    fwd: &HashMap<&'a str, Vec<&'a str>>,
-    rev: &HashMap<&'a str, Vec<&'a str>>,
-) -> HashMap<(&'a str, &'a str), usize> {
-    let mut occ = HashMap::new();
-    for &start in fwd.keys() {
-        let (mut queue, mut seen) = (VecDeque::new(), HashSet::new());
-        queue.push_back(start);
-        seen.insert(start);
-        while let Some(n) = queue.pop_front() {
-            let nxt = fwd[n].iter().map(|&m| (m, (n, m)));
-            let prev = rev[n].iter().map(|&m| (m, (m, n)));
-            for (m, e) in nxt.chain(prev) {
-                if seen.insert(m) {
-                    *occ.entry(e).or_default() += 1;
-                    queue.push_back(m);
-                }
-            }
-        }
-    }
-    occ
-}
+use crate::{
+    day::{Answer, Day, Expected, ParseError, SolveError},
+    trace,
+    util::{
+        flow,
+        graph::{Graph, GraphBuilder},
+        Ignore,
+    },
+};
 
 pub struct Day25;
 
 impl<'a> Day<'a> for Day25 {
     const DAY: usize = 25;
 
-    type Input = HashMap<&'a str, Vec<&'a str>>;
-    type ProcessedInput = ();
+    type Input = Graph;
+    type Shared = ();
 
-    fn parse(input: &'a str) -> Self::Input {
-        input
-            .lines()
-            .map(|line| {
-                let (k, vs) = line.split_once(": ").unwrap();
-                (k, vs.split_whitespace().collect())
-            })
-            .collect()
+    fn parse(input: &'a str) -> Result<Self::Input, ParseError> {
+        let mut builder = GraphBuilder::undirected();
+        for line in input.lines() {
+            let (k, vs) = line.split_once(": ").expected("': '")?;
+            vs.split_whitespace()
+                .for_each(|v| builder.edge(k, v, ()).ignore());
+        }
+        Ok(builder.build().0)
     }
 
-    fn solve_part1(mut fwd: Self::Input) -> (Self::ProcessedInput, String) {
-        let (fwd, rev) = {
-            let mut rev = HashMap::<&'a str, Vec<&'a str>>::new();
-            fwd.iter()
-                .for_each(|(k, vs)| vs.iter().for_each(|v| rev.entry(*v).or_default().push(*k)));
-            rev.keys().for_each(|k| fwd.entry(k).or_default().ignore());
-            fwd.keys().for_each(|k| rev.entry(k).or_default().ignore());
-            (fwd, rev)
-        };
-        let occ = count_edge_occurences(&fwd, &rev);
-        let edges = {
-            let mut edges = fwd
-                .iter()
-                .flat_map(|(&k, vs)| vs.iter().map(move |&v| (k, v)))
-                .collect::<Vec<_>>();
-            edges.sort_unstable_by_key(|e| occ[e]);
-            edges.reverse();
-            edges
-        };
-        for forbid in edges.into_iter().combinations(3) {
-            let start = fwd.keys().copied().next().unwrap();
-            let mut queue = vec![start];
-            let mut seen = queue.iter().copied().collect::<HashSet<_>>();
-            while let Some(k) = queue.pop() {
-                let nxt = fwd[k].iter().filter(|e| !forbid.contains(&(k, e)));
-                let prev = rev[k].iter().filter(|e| !forbid.contains(&(e, k)));
-                let l = queue.len();
-                queue.extend(nxt.chain(prev).filter(|&e| !seen.contains(e)));
-                seen.extend(queue.iter().copied().skip(l));
-            }
-            if seen.len() < fwd.len() {
-                let ans = seen.len() * (fwd.len() - seen.len());
-                return ((), ans.to_string());
-            }
+    fn prepare(_: &Self::Input) -> Result<(), SolveError> {
+        Ok(())
+    }
+
+    fn part1(graph: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        let cut = flow::global_min_cut(graph, |_| 1)
+            .ok_or_else(|| SolveError::new("fewer than two nodes to separate"))?;
+        trace!(Debug, "cut", weight = cut.weight, edges = cut.edges);
+        if cut.weight == 0 {
+            return Err(SolveError::new("components are already disconnected"));
         }
-        panic!("no cut found")
+        Ok((cut.parts[0].len() * cut.parts[1].len()).into())
     }
 
-    fn solve_part2(_: Self::ProcessedInput) -> String {
-        "Merry Christmas!".to_string()
+    fn part2(_: &Self::Input, _: &()) -> Result<Answer, SolveError> {
+        Ok("Merry Christmas!".into())
     }
 }
 
@@ -111,8 +68,20 @@ mod test_day25 {
 
     #[test]
     fn test_day25_examples() {
-        let input = Day25::parse(EXAMPLE);
-        let (_, part1) = Day25::solve_part1(input);
+        let input = Day25::parse(EXAMPLE).unwrap();
+        let part1 = Day25::part1(&input, &()).unwrap();
         assert_eq!(part1, "54");
+
+        // two triangles joined by a single wire
+        let triangles = "aaa: bbb ccc\nbbb: ccc\nddd: eee fff\neee: fff";
+        let input = Day25::parse(&format!("{}\nccc: ddd", triangles)).unwrap();
+        assert_eq!(Day25::part1(&input, &()).unwrap(), "9");
+        let input = Day25::parse(&format!("{}\nddd: ggg\nggg: aaa bbb", triangles)).unwrap();
+        assert_eq!(Day25::part1(&input, &()).unwrap(), "12");
+        let input = Day25::parse(triangles).unwrap();
+        let err = Day25::part1(&input, &()).unwrap_err();
+        assert_eq!(err.to_string(), "components are already disconnected");
+        let input = Day25::parse("aaa: aaa").unwrap();
+        assert!(Day25::part1(&input, &()).is_err());
     }
 }
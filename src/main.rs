@@ -1,13 +1,65 @@
-mod day;
-mod util;
+use std::io;
 
-use day::Day;
+use aoc2023::{
+    day::{self, Day, Part, Run},
+    repl, trace,
+    viz::{Output, Visualize},
+};
+
+#[derive(Default)]
+struct Args {
+    day: Option<String>,
+    viz: Option<Output>,
+    repl: bool,
+    run: Run,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Args::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--viz" => {
+                    let spec = iter.next().ok_or(
+                        "Expected visualization output after --viz \
+                        (term, ppm[=DIR], png[=DIR] or gif[=FILE])",
+                    )?;
+                    let output = Output::parse(&spec)
+                        .ok_or_else(|| format!("Unrecognized visualization output \"{}\"", spec))?;
+                    args.viz = Some(output);
+                }
+                "--trace" => {
+                    let spec = iter
+                        .next()
+                        .ok_or("Expected targets after --trace (e.g. day20=debug,day08=trace)")?;
+                    trace::configure(&spec)?;
+                }
+                "--part" => {
+                    args.run.only = match iter.next().as_deref() {
+                        Some("1") => Some(Part::One),
+                        Some("2") => Some(Part::Two),
+                        _ => return Err("Expected 1 or 2 after --part".to_string()),
+                    };
+                }
+                "--concurrent" => args.run.concurrent = true,
+                "--threads" => {
+                    let spec = iter.next().ok_or("Expected a number after --threads")?;
+                    day::set_threads(day::parse_threads(&spec)?);
+                }
+                "repl" if args.day.is_none() && !args.repl => args.repl = true,
+                _ if args.day.is_none() => args.day = Some(arg),
+                _ => return Err(format!("Unexpected argument \"{}\"", arg)),
+            }
+        }
+        Ok(args)
+    }
+}
 
 macro_rules! import_days {
     ($day:literal) => {
         paste::paste! {
-            mod [<day $day>];
-            use [<day $day>]::[<Day $day>];
+            use aoc2023::[<day $day>]::[<Day $day>];
         }
     };
     ($day:literal, $($days:literal),+) => {
@@ -17,27 +69,28 @@ macro_rules! import_days {
 }
 
 macro_rules! solve {
-    ($day:literal) => {{
+    ($run:ident, $day:literal) => {{
         paste::paste! {
-            match [<Day $day>]::get_input() {
-                Ok(input) => [<Day $day>]::solve_and_print(&input),
-                Err(err) => eprintln!("{}", err),
+            let solved = [<Day $day>]::get_input()
+                .and_then(|input| [<Day $day>]::solve_and_print(&input, $run));
+            if let Err(err) = solved {
+                eprintln!("{}", err);
             }
         }
     }};
-    ($day:literal, $($days:literal),+) => {{
-        solve!($day);
-        solve!($($days),+)
+    ($run:ident, $day:literal, $($days:literal),+) => {{
+        solve!($run, $day);
+        solve!($run, $($days),+)
     }}
 }
 
 macro_rules! match_days {
-    ($day_string:ident, $($days:literal),+) => {{
+    ($day_string:ident, $run:ident, $($days:literal),+) => {{
         match $day_string {
-            "all" => solve!($($days),+),
+            "all" => solve!($run, $($days),+),
             day => match day.parse::<usize>() {
                 Err(err) => eprintln!("Expected day number (or \"all\") as argument ({})", err),
-                $(Ok($days) => solve!($days)),+,
+                $(Ok($days) => solve!($run, $days)),+,
                 Ok(n) if (1..=25).contains(&n) => todo!(),
                 Ok(_) => eprintln!("That's not a day of advent!"),
             }
@@ -45,25 +98,75 @@ macro_rules! match_days {
     }}
 }
 
+macro_rules! visualize {
+    ($day_string:ident, $output:ident, $($days:literal),+) => {{
+        match $day_string.parse::<usize>() {
+            $(Ok($days) => paste::paste! {{
+                let visualized = [<Day $days>]::get_input()
+                    .and_then(|input| [<Day $days>]::parse_and_visualize(&input, $output));
+                if let Err(err) = visualized {
+                    eprintln!("{}", err);
+                }
+            }}),+,
+            _ => eprintln!(
+                "Expected the number of a day with a visualization ({})",
+                stringify!($($days),+)
+            ),
+        }
+    }}
+}
+
+macro_rules! repl {
+    ($day_string:ident, $($days:literal),+) => {{
+        match $day_string.parse::<usize>() {
+            $(Ok($days) => paste::paste! {{
+                let ran = [<Day $days>]::get_input().and_then(|input| {
+                    let (stdin, mut stdout) = (io::stdin().lock(), io::stdout());
+                    repl::run::<[<Day $days>], _, _>(&input, stdin, &mut stdout)
+                });
+                if let Err(err) = ran {
+                    eprintln!("{}", err);
+                }
+            }}),+,
+            _ => eprintln!("Expected day number as argument to repl"),
+        }
+    }}
+}
+
 macro_rules! declare_main {
-    ($last_day:literal => $($days:literal),+) => {
+    ($last_day:literal => $($days:literal),+; $($viz_days:literal),+) => {
         import_days!($($days),+);
         fn main() {
+            let args = match Args::parse() {
+                Ok(args) => args,
+                Err(err) => return eprintln!("{}", err),
+            };
+            let run = args.run;
             #[allow(clippy::zero_prefixed_literal)]
-            match std::env::args().nth(1).as_deref() {
-                None => solve!($last_day),
-                Some(day) => match_days!(day, $($days),+),
+            match (args.day.as_deref(), &args.viz, args.repl) {
+                (_, Some(_), true) => eprintln!("Can't visualize from the repl"),
+                (day, None, true) => {
+                    let day = day.unwrap_or(stringify!($last_day));
+                    repl!(day, $($days),+)
+                }
+                (day, Some(output), false) => {
+                    let day = day.unwrap_or(stringify!($last_day));
+                    visualize!(day, output, $($viz_days),+)
+                }
+                (None, None, false) => solve!(run, $last_day),
+                (Some(day), None, false) => match_days!(day, run, $($days),+),
             }
         }
     };
-    ($head_day:literal, $($last_days:literal),+ => $($days:literal),+) => {
-        declare_main!($($last_days),+ => $($days),+);
+    ($head_day:literal, $($last_days:literal),+ => $($days:literal),+; $($viz_days:literal),+) => {
+        declare_main!($($last_days),+ => $($days),+; $($viz_days),+);
     };
-    ($($days:literal),+) => {
-        declare_main!($($days),+ => $($days),+);
+    ($($days:literal),+; visualize $($viz_days:literal),+) => {
+        declare_main!($($days),+ => $($days),+; $($viz_days),+);
     }
 }
 
 declare_main!(
-    01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25
+    01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25;
+    visualize 10, 16, 18, 21
 );
@@ -0,0 +1,145 @@
+use std::io::{self, BufRead, Write};
+
+use crate::day::{timed, Day, Error, Part, SolveError};
+
+const COMMANDS: [(&str, &str); 7] = [
+    ("part1", "run part 1 on the parsed input"),
+    ("part2", "run part 2 on the parsed input"),
+    ("prepare", "redo the work both parts share"),
+    (
+        "time <n>",
+        "prepare and run both parts n times and report the mean times",
+    ),
+    ("input", "print the parsed input"),
+    ("help", "list the commands"),
+    ("quit", "leave the repl"),
+];
+
+fn time_parts<'a, D: Day<'a>>(input: &D::Input) -> Result<[f32; 3], SolveError> {
+    let (shared, prepare) = timed(|| D::prepare(input));
+    let shared = shared?;
+    let (solved, part1) = timed(|| D::part1(input, &shared));
+    solved?;
+    let (solved, part2) = timed(|| D::part2(input, &shared));
+    solved.map(|_| [prepare, part1, part2])
+}
+
+fn print_help<'a, D: Day<'a>, W: Write>(out: &mut W) -> io::Result<()> {
+    for (usage, description) in COMMANDS.iter().chain(D::QUERIES) {
+        writeln!(out, "  {:<20} {}", usage, description)?;
+    }
+    Ok(())
+}
+
+fn command<'a, D: Day<'a>, W: Write>(
+    input: &D::Input,
+    shared: &mut D::Shared,
+    line: &str,
+    out: &mut W,
+) -> io::Result<bool> {
+    let mut words = line.split_whitespace();
+    let Some(cmd) = words.next() else {
+        return Ok(true);
+    };
+    let args = words.collect::<Vec<_>>();
+    match (cmd, args.as_slice()) {
+        ("quit" | "exit", []) => return Ok(false),
+        ("help", []) => print_help::<D, W>(out)?,
+        ("input", []) => writeln!(out, "{:#?}", input)?,
+        ("part1" | "part2", []) => {
+            let part = if cmd == "part1" { Part::One } else { Part::Two };
+            match timed(|| D::solve(part, input, shared)) {
+                (Ok(answer), elapsed) => {
+                    writeln!(out, "  {}: {} (elapsed {}ms)", part, answer, elapsed)?
+                }
+                (Err(err), _) => writeln!(out, "  {} failed: {}", part, err)?,
+            }
+        }
+        ("prepare", []) => match timed(|| D::prepare(input)) {
+            (Ok(prepared), elapsed) => {
+                *shared = prepared;
+                writeln!(out, "  prepared (elapsed {}ms)", elapsed)?
+            }
+            (Err(err), _) => writeln!(out, "  prepare failed: {}", err)?,
+        },
+        ("time", [n]) => match n.parse::<usize>() {
+            Ok(n) if n > 0 => {
+                let times = (0..n).map(|_| time_parts::<D>(input));
+                match times.collect::<Result<Vec<_>, _>>() {
+                    Ok(times) => {
+                        let runs = n as f32;
+                        for (i, name) in ["prepare", "part1", "part2"].iter().enumerate() {
+                            let mean = times.iter().map(|t| t[i]).sum::<f32>() / runs;
+                            writeln!(out, "  {}: mean {}ms over {} runs", name, mean, n)?;
+                        }
+                    }
+                    Err(err) => writeln!(out, "  failed: {}", err)?,
+                }
+            }
+            _ => writeln!(out, "Expected a positive number of runs")?,
+        },
+        _ => match D::query(input, cmd, &args) {
+            Ok(result) => writeln!(out, "{}", result)?,
+            Err(err) => writeln!(out, "{} (try \"help\")", err)?,
+        },
+    }
+    Ok(true)
+}
+
+pub fn run<'a, D: Day<'a>, R: BufRead, W: Write>(
+    input: &'a str,
+    mut commands: R,
+    out: &mut W,
+) -> Result<(), Error> {
+    let input = D::parse(input).map_err(|err| Error::ParsingInput(D::DAY, err))?;
+    let mut shared = D::prepare(&input).map_err(|err| Error::Solving(D::DAY, err))?;
+    let mut line = String::new();
+    loop {
+        write!(out, "day{:02}> ", D::DAY).map_err(Error::Interacting)?;
+        out.flush().map_err(Error::Interacting)?;
+        line.clear();
+        if commands.read_line(&mut line).map_err(Error::Interacting)? == 0 {
+            return writeln!(out).map_err(Error::Interacting);
+        }
+        if !command::<D, W>(&input, &mut shared, &line, out).map_err(Error::Interacting)? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_repl {
+    use super::*;
+    use crate::day15::Day15;
+
+    #[test]
+    fn test_repl_commands() {
+        let commands = "part1\n\nhash HASH\npart2\nprepare\ntime 0\nfrobnicate\nquit\npart1\n";
+        let mut out = Vec::new();
+        run::<Day15, _, _>(
+            "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7",
+            commands.as_bytes(),
+            &mut out,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out
+            .split("day15> ")
+            .map(|l| l.split(" (").next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "",
+                "  part1: 1320",
+                "",
+                "52\n",
+                "  part2: 145",
+                "  prepared",
+                "Expected a positive number of runs\n",
+                "Unknown command \"frobnicate\"",
+                "",
+            ]
+        );
+    }
+}
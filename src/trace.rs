@@ -0,0 +1,145 @@
+use std::{
+    fmt::Debug,
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info = 1,
+    Debug = 2,
+    Trace = 3,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(format!("Unknown trace level \"{}\"", s)),
+        }
+    }
+}
+
+// target 0 collects events from outside the day modules
+const TARGETS: usize = 26;
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+static LEVELS: [AtomicU8; TARGETS] = [const { AtomicU8::new(0) }; TARGETS];
+
+pub const fn target_of(module: &str) -> usize {
+    let bs = module.as_bytes();
+    let mut i = bs.len();
+    while i > 0 && bs[i - 1] != b':' {
+        i -= 1;
+    }
+    match bs.len() - i {
+        5 if bs[i] == b'd' && bs[i + 1] == b'a' && bs[i + 2] == b'y' => {
+            let (d0, d1) = (bs[i + 3].wrapping_sub(b'0'), bs[i + 4].wrapping_sub(b'0'));
+            match (d0, d1) {
+                (0..=9, 0..=9) if 10 * d0 + d1 < TARGETS as u8 => (10 * d0 + d1) as usize,
+                _ => 0,
+            }
+        }
+        _ => 0,
+    }
+}
+
+#[inline(always)]
+pub fn enabled(target: usize, level: Level) -> bool {
+    let level = level as u8;
+    MAX_LEVEL.load(Ordering::Relaxed) >= level && LEVELS[target].load(Ordering::Relaxed) >= level
+}
+
+pub fn set_level(target: usize, level: Level) {
+    LEVELS[target].store(level as u8, Ordering::Relaxed);
+    MAX_LEVEL.fetch_max(level as u8, Ordering::Relaxed);
+}
+
+fn parse_spec(spec: &str) -> Result<Vec<(Option<usize>, Level)>, String> {
+    spec.split(',')
+        .map(|directive| {
+            let (target, level) = directive.split_once('=').unwrap_or((directive, "debug"));
+            let target = match target {
+                "all" => None,
+                _ => target
+                    .strip_prefix("day")
+                    .and_then(|n| n.parse().ok())
+                    .filter(|n| (1..TARGETS).contains(n))
+                    .map(Some)
+                    .ok_or_else(|| format!("Unknown trace target \"{}\"", target))?,
+            };
+            Ok((target, level.parse()?))
+        })
+        .collect()
+}
+
+pub fn configure(spec: &str) -> Result<(), String> {
+    for (target, level) in parse_spec(spec)? {
+        match target {
+            Some(t) => set_level(t, level),
+            None => (0..TARGETS).for_each(|t| set_level(t, level)),
+        }
+    }
+    Ok(())
+}
+
+pub fn emit(target: usize, level: Level, message: &str, fields: &[(&str, &dyn Debug)]) {
+    let mut line = match target {
+        0 => format!("[{}] {}", level.name(), message),
+        day => format!("[day{:02} {}] {}", day, level.name(), message),
+    };
+    for (name, value) in fields {
+        line.push_str(&format!(" {}={:?}", name, value));
+    }
+    eprintln!("{}", line);
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($level:ident, $message:literal $(, $field:ident = $value:expr)* $(,)?) => {{
+        const TARGET: usize = $crate::trace::target_of(module_path!());
+        let level = $crate::trace::Level::$level;
+        if $crate::trace::enabled(TARGET, level) {
+            let fields: &[(&str, &dyn std::fmt::Debug)] = &[$((stringify!($field), &$value)),*];
+            $crate::trace::emit(TARGET, level, $message, fields);
+        }
+    }};
+}
+
+#[cfg(test)]
+mod test_trace {
+    use super::*;
+
+    #[test]
+    fn test_trace_targets() {
+        assert_eq!(target_of("aoc2023::day08"), 8);
+        assert_eq!(target_of("aoc2023::day25"), 25);
+        assert_eq!(target_of("aoc2023::day99"), 0);
+        assert_eq!(target_of("aoc2023::day08::test_day08"), 0);
+        assert_eq!(target_of("aoc2023::util"), 0);
+        assert_eq!(
+            parse_spec("day20=trace,day08,all=info"),
+            Ok(vec![
+                (Some(20), Level::Trace),
+                (Some(8), Level::Debug),
+                (None, Level::Info)
+            ])
+        );
+        assert!(parse_spec("day26=debug").is_err());
+        assert!(parse_spec("day20=loud").is_err());
+    }
+}
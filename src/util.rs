@@ -1,7 +1,34 @@
-use std::ops::Range;
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Display, Formatter},
+    ops::Range,
+};
 
 use itertools::Itertools;
 
+use crate::day::ParseError;
+
+pub mod bitset;
+pub mod cycle;
+pub mod flow;
+pub mod geometry;
+pub mod graph;
+mod grid;
+pub mod hash;
+pub mod interval;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod parse;
+mod point;
+pub mod poly;
+pub mod search;
+mod view;
+
+pub use grid::Grid;
+pub use point::{Dir4, Dir8, Point};
+pub use view::{Bounds, GridView, SparseGrid, Tiled};
+
 pub trait Ignore {
     fn ignore(self);
 }
@@ -10,22 +37,76 @@ impl<T> Ignore for T {
     fn ignore(self) {}
 }
 
+// rows of bytes laid out `stride` apart in one buffer, which borrows the input when
+// every line ends the same way and is a packed copy otherwise
+#[derive(Clone)]
 pub struct LineGrid<'a> {
-    lines: Vec<&'a [u8]>,
+    bytes: Cow<'a, [u8]>,
+    stride: usize,
     width: usize,
     height: usize,
 }
 
 impl<'a> LineGrid<'a> {
+    // panics on ragged input, for tests and literals
     pub fn new(string: &'a str) -> Self {
-        let lines = string.trim().lines().map(str::as_bytes).collect::<Vec<_>>();
+        Self::parse(string).unwrap_or_else(|err| panic!("invalid grid: {}", err))
+    }
+
+    // accepts LF or CRLF line endings and checks every line has the same width, counting
+    // cells as bytes, which is why they have to be ASCII
+    pub fn parse(string: &'a str) -> Result<Self, ParseError> {
+        let string = string.trim_matches(['\r', '\n']);
+        if string.is_empty() {
+            return Ok(LineGrid {
+                bytes: Cow::Borrowed(&[]),
+                stride: 0,
+                width: 0,
+                height: 0,
+            });
+        }
+        let lines = string
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l).as_bytes())
+            .collect::<Vec<_>>();
+        if let Some(i) = lines.iter().position(|l| !l.is_ascii()) {
+            return Err(ParseError::new(format!(
+                "expected ASCII cells on line {}",
+                i + 1
+            )));
+        }
         let width = lines[0].len();
-        let height = lines.len();
-        LineGrid {
-            lines,
-            width,
-            height,
+        if let Some(i) = lines.iter().position(|l| l.len() != width) {
+            return Err(ParseError::new(format!(
+                "expected {} cells on line {} but found {}",
+                width,
+                i + 1,
+                lines[i].len()
+            )));
         }
+        let offset = |l: &[u8]| l.as_ptr() as usize - string.as_ptr() as usize;
+        let stride = lines.get(1).map_or(width, |l| offset(l));
+        let uniform = lines
+            .iter()
+            .enumerate()
+            .all(|(i, l)| offset(l) == i * stride);
+        let (bytes, stride) = match uniform {
+            true => {
+                let end = (lines.len() - 1) * stride + width;
+                (Cow::Borrowed(&string.as_bytes()[..end]), stride)
+            }
+            false => (Cow::Owned(lines.concat()), width),
+        };
+        Ok(LineGrid {
+            bytes,
+            stride,
+            width,
+            height: lines.len(),
+        })
+    }
+
+    fn row(&self, row: usize) -> &[u8] {
+        &self.bytes[row * self.stride..row * self.stride + self.width]
     }
 
     pub fn in_bounds(&self, row: i32, col: i32) -> bool {
@@ -34,12 +115,24 @@ impl<'a> LineGrid<'a> {
     }
 
     pub fn get(&self, row: usize, col: usize) -> Option<char> {
-        (row < self.height() && col < self.width()).then(|| self.lines[row][col] as char)
+        (row < self.height() && col < self.width()).then(|| self.row(row)[col] as char)
     }
 
     pub fn try_get(&self, row: i32, col: i32) -> Option<char> {
         let (r, c) = (row as usize, col as usize);
-        self.in_bounds(row, col).then(|| self.lines[r][c] as char)
+        self.in_bounds(row, col).then(|| self.row(r)[c] as char)
+    }
+
+    pub fn get_point(&self, point: Point) -> Option<char> {
+        self.try_get(point.row, point.col)
+    }
+
+    pub fn neighbours4(&self, point: Point) -> impl '_ + Iterator<Item = Point> {
+        point.neighbours4().filter(|p| self.in_bounds(p.row, p.col))
+    }
+
+    pub fn neighbours8(&self, point: Point) -> impl '_ + Iterator<Item = Point> {
+        point.neighbours8().filter(|p| self.in_bounds(p.row, p.col))
     }
 
     pub fn width(&self) -> usize {
@@ -50,13 +143,16 @@ impl<'a> LineGrid<'a> {
         self.height
     }
 
-    pub fn section(&self, row: usize, range: Range<usize>) -> &'a str {
-        std::str::from_utf8(&self.lines[row][range]).unwrap()
+    // borrows from the grid rather than the input, since a grid with mixed line endings
+    // holds a packed copy
+    pub fn section(&self, row: usize, range: Range<usize>) -> &str {
+        std::str::from_utf8(&self.row(row)[range]).expect("grid should be ASCII")
     }
 
     pub fn iter(&self) -> impl '_ + Iterator<Item = (usize, usize, char)> {
-        self.lines.iter().enumerate().flat_map(|(i, row)| {
-            row.iter()
+        (0..self.height()).flat_map(move |i| {
+            self.row(i)
+                .iter()
                 .copied()
                 .enumerate()
                 .map(move |(j, c)| (i, j, c as char))
@@ -64,27 +160,52 @@ impl<'a> LineGrid<'a> {
     }
 
     pub fn iter_row(&self, row: usize) -> impl '_ + Iterator<Item = char> {
-        self.lines[row].iter().map(|&c| c as char)
+        self.row(row).iter().map(|&c| c as char)
     }
 
     pub fn iter_col(&self, col: usize) -> impl '_ + Iterator<Item = char> {
-        (0..self.height()).map(move |r| self.lines[r][col] as char)
+        (0..self.height()).map(move |r| self.row(r)[col] as char)
     }
 }
 
-impl<'a> ToString for LineGrid<'a> {
-    fn to_string(&self) -> String {
-        self.lines
-            .iter()
-            .map(|l| std::str::from_utf8(l).unwrap())
-            .join("\n")
+impl<'a> Debug for LineGrid<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let lines = (0..self.height).map(|r| String::from_utf8_lossy(self.row(r)));
+        f.debug_struct("LineGrid")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("lines", &lines.collect::<Vec<_>>())
+            .finish()
     }
 }
 
-pub fn grid_neighbours(tile: (usize, usize)) -> impl Iterator<Item = (i32, i32)> {
-    [-1, 0, 1].into_iter().flat_map(move |i| {
-        [-1, 0, 1]
-            .into_iter()
-            .map(move |j| (tile.0 as i32 + i, tile.1 as i32 + j))
-    })
+impl<'a> Display for LineGrid<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let lines = (0..self.height).map(|r| String::from_utf8_lossy(self.row(r)));
+        write!(f, "{}", lines.format("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test_util {
+    use super::*;
+
+    #[test]
+    fn test_line_grid_parse() {
+        let crlf = LineGrid::parse("\r\nabc\r\ndef\r\n").unwrap();
+        assert_eq!(crlf.to_string(), "abc\ndef");
+        assert_eq!(Grid::from(&crlf), Grid::from(&LineGrid::new("abc\ndef")));
+        let mixed = LineGrid::parse("abc\r\ndef\nghi").unwrap();
+        assert_eq!(mixed.iter_col(1).collect::<String>(), "beh");
+        assert_eq!(mixed.section(2, 1..3), "hi");
+        assert_eq!(
+            LineGrid::parse("abc\nde\nfgh").unwrap_err(),
+            ParseError::new("expected 3 cells on line 2 but found 2")
+        );
+        assert_eq!(
+            LineGrid::parse("ab\ncé").unwrap_err(),
+            ParseError::new("expected ASCII cells on line 2")
+        );
+        assert_eq!(LineGrid::parse("\n").unwrap().height(), 0);
+    }
 }
@@ -0,0 +1,268 @@
+use std::{
+    hash::{Hash, Hasher},
+    ops::{BitAnd, BitOr, Sub},
+};
+
+use super::Ignore;
+
+// the operations search states need, shared by the fixed and growable sets
+pub trait Bits: Clone + Eq + Hash {
+    fn contains(&self, i: usize) -> bool;
+    fn insert(&mut self, i: usize) -> bool;
+
+    fn with(&self, i: usize) -> Self {
+        let mut set = self.clone();
+        set.insert(i);
+        set
+    }
+}
+
+fn ones(words: &[u64]) -> impl '_ + Iterator<Item = usize> {
+    words.iter().enumerate().flat_map(|(w, &word)| {
+        let mut word = word;
+        std::iter::from_fn(move || {
+            (word != 0).then(|| {
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                64 * w + bit
+            })
+        })
+    })
+}
+
+// holds 0..64 * W
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BitSet<const W: usize> {
+    words: [u64; W],
+}
+
+impl<const W: usize> BitSet<W> {
+    pub const CAPACITY: usize = 64 * W;
+
+    pub const fn new() -> Self {
+        BitSet { words: [0; W] }
+    }
+
+    pub fn remove(&mut self, i: usize) -> bool {
+        let had = self.contains(i);
+        if had {
+            self.words[i / 64] &= !(1 << (i % 64));
+        }
+        had
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn iter(&self) -> impl '_ + Iterator<Item = usize> {
+        ones(&self.words)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        (0..W).all(|w| self.words[w] & !other.words[w] == 0)
+    }
+
+    fn zip_with<F: Fn(u64, u64) -> u64>(&self, other: &Self, f: F) -> Self {
+        BitSet {
+            words: std::array::from_fn(|w| f(self.words[w], other.words[w])),
+        }
+    }
+}
+
+impl<const W: usize> Bits for BitSet<W> {
+    fn contains(&self, i: usize) -> bool {
+        i < Self::CAPACITY && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn insert(&mut self, i: usize) -> bool {
+        assert!(i < Self::CAPACITY, "{} doesn't fit in a BitSet<{}>", i, W);
+        let had = self.contains(i);
+        self.words[i / 64] |= 1 << (i % 64);
+        !had
+    }
+}
+
+impl<const W: usize> Default for BitSet<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize> Hash for BitSet<W> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.words.iter().for_each(|&w| state.write_u64(w));
+    }
+}
+
+impl<const W: usize> BitOr for BitSet<W> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a | b)
+    }
+}
+
+impl<const W: usize> BitAnd for BitSet<W> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a & b)
+    }
+}
+
+impl<const W: usize> Sub for BitSet<W> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a & !b)
+    }
+}
+
+impl<const W: usize> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|i| set.insert(i).ignore());
+        set
+    }
+}
+
+// trailing zero words are trimmed so equal sets compare and hash equal
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GrowBitSet {
+    words: Vec<u64>,
+}
+
+impl GrowBitSet {
+    pub fn new() -> Self {
+        GrowBitSet { words: Vec::new() }
+    }
+
+    fn trim(mut self) -> Self {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+        self
+    }
+
+    pub fn remove(&mut self, i: usize) -> bool {
+        let had = self.contains(i);
+        if had {
+            self.words[i / 64] &= !(1 << (i % 64));
+            *self = std::mem::take(self).trim();
+        }
+        had
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn iter(&self) -> impl '_ + Iterator<Item = usize> {
+        ones(&self.words)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let word = |set: &Self, w: usize| set.words.get(w).copied().unwrap_or(0);
+        (0..self.words.len()).all(|w| word(self, w) & !word(other, w) == 0)
+    }
+
+    fn zip_with<F: Fn(u64, u64) -> u64>(&self, other: &Self, f: F) -> Self {
+        let word = |set: &Self, w: usize| set.words.get(w).copied().unwrap_or(0);
+        let len = self.words.len().max(other.words.len());
+        let words = (0..len).map(|w| f(word(self, w), word(other, w)));
+        GrowBitSet {
+            words: words.collect(),
+        }
+        .trim()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & !b)
+    }
+}
+
+impl Bits for GrowBitSet {
+    fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .is_some_and(|w| w & (1 << (i % 64)) != 0)
+    }
+
+    fn insert(&mut self, i: usize) -> bool {
+        if self.words.len() <= i / 64 {
+            self.words.resize(i / 64 + 1, 0);
+        }
+        let had = self.contains(i);
+        self.words[i / 64] |= 1 << (i % 64);
+        !had
+    }
+}
+
+impl Hash for GrowBitSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.words.iter().for_each(|&w| state.write_u64(w));
+    }
+}
+
+impl FromIterator<usize> for GrowBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|i| set.insert(i).ignore());
+        set
+    }
+}
+
+#[cfg(test)]
+mod test_bitset {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    proptest! {
+        #[test]
+        fn test_bitset_ops(
+            a in prop::collection::btree_set(0..200usize, 0..20),
+            b in prop::collection::btree_set(0..200usize, 0..20),
+        ) {
+            let (fa, fb) = (a.iter().copied().collect::<BitSet<4>>(), b.iter().copied().collect::<BitSet<4>>());
+            let (ga, gb) = (a.iter().copied().collect::<GrowBitSet>(), b.iter().copied().collect::<GrowBitSet>());
+            let set = |it: &mut dyn Iterator<Item = usize>| it.collect::<BTreeSet<_>>();
+            prop_assert_eq!(set(&mut fa.iter()), a.clone());
+            prop_assert_eq!(set(&mut (fa | fb).iter()), &a | &b);
+            prop_assert_eq!(set(&mut (fa & fb).iter()), &a & &b);
+            prop_assert_eq!(set(&mut (fa - fb).iter()), &a - &b);
+            prop_assert_eq!(set(&mut ga.union(&gb).iter()), &a | &b);
+            prop_assert_eq!(set(&mut ga.intersection(&gb).iter()), &a & &b);
+            prop_assert_eq!(set(&mut ga.difference(&gb).iter()), &a - &b);
+            prop_assert_eq!((fa.len(), ga.len()), (a.len(), a.len()));
+            prop_assert_eq!(fa.is_subset(&fb), a.is_subset(&b));
+            prop_assert_eq!(ga.is_subset(&gb), a.is_subset(&b));
+            prop_assert!((0..200).all(|i| fa.contains(i) == a.contains(&i) && ga.contains(i) == a.contains(&i)));
+            let mut removed = ga.clone();
+            a.iter().for_each(|&i| removed.remove(i).ignore());
+            prop_assert_eq!(removed, GrowBitSet::new());
+            let mut removed = fa;
+            prop_assert!(!removed.remove(BitSet::<4>::CAPACITY));
+            a.iter().for_each(|&i| removed.remove(i).ignore());
+            prop_assert_eq!(removed, BitSet::new());
+            prop_assert_eq!(fa.with(7).contains(7), true);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::{collections::hash_map::Entry, hash::Hash};
+
+use super::hash::FastHashMap;
+
+// states from `prefix` onwards repeat every `period` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    // the first step whose state is the same as after n steps
+    pub fn reduce(&self, n: usize) -> usize {
+        match n < self.prefix {
+            true => n,
+            false => self.prefix + (n - self.prefix) % self.period,
+        }
+    }
+}
+
+// remembers the step each key was first seen at, so keys must identify states exactly
+pub fn find_by_key<S, K, F, G>(start: &S, mut step: F, mut key: G) -> Cycle
+where
+    S: Clone,
+    K: Eq + Hash,
+    F: FnMut(&mut S),
+    G: FnMut(&S) -> K,
+{
+    let (mut seen, mut state) = (FastHashMap::default(), start.clone());
+    for i in 0.. {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(e) => {
+                let prefix = *e.get();
+                return Cycle {
+                    prefix,
+                    period: i - prefix,
+                };
+            }
+            Entry::Vacant(e) => e.insert(i),
+        };
+        step(&mut state);
+    }
+    unreachable!()
+}
+
+pub fn find<S, F>(start: &S, step: F) -> Cycle
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&mut S),
+{
+    find_by_key(start, step, S::clone)
+}
+
+// Brent's algorithm, which only ever holds two states
+pub fn brent<S, F>(start: &S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&mut S),
+{
+    let (mut power, mut period) = (1, 1);
+    let (mut tortoise, mut hare) = (start.clone(), start.clone());
+    step(&mut hare);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        step(&mut hare);
+        period += 1;
+    }
+    let (mut tortoise, mut hare) = (start.clone(), start.clone());
+    (0..period).for_each(|_| step(&mut hare));
+    let mut prefix = 0;
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+pub fn state_after<S: Clone, F: FnMut(&mut S)>(
+    start: &S,
+    mut step: F,
+    cycle: &Cycle,
+    n: usize,
+) -> S {
+    let mut state = start.clone();
+    (0..cycle.reduce(n)).for_each(|_| step(&mut state));
+    state
+}
+
+#[cfg(test)]
+mod test_cycle {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_cycle_finders(next in prop::collection::vec(0..20usize, 20), start in 0..20usize) {
+            let step = |s: &mut usize| *s = next[*s];
+            let mut states = vec![start];
+            while !states[..states.len() - 1].contains(states.last().unwrap()) {
+                states.push(next[*states.last().unwrap()]);
+            }
+            let last = states.pop().unwrap();
+            let prefix = states.iter().position(|&s| s == last).unwrap();
+            let cycle = Cycle { prefix, period: states.len() - prefix };
+            prop_assert_eq!(find(&start, step), cycle);
+            prop_assert_eq!(brent(&start, step), cycle);
+            prop_assert_eq!(find_by_key(&start, step, |&s| s as u8), cycle);
+            let brute = (0..1000).fold(start, |s, _| next[s]);
+            prop_assert_eq!(state_after(&start, step, &cycle, 1000), brute);
+        }
+    }
+}
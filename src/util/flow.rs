@@ -0,0 +1,289 @@
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::{graph::Graph, hash::FastHashMap};
+
+// residual capacities, with each edge stored next to its reverse so that `e ^ 1` finds it
+#[derive(Debug, Clone)]
+pub struct FlowNetwork {
+    adj: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    cap: Vec<i64>,
+}
+
+impl FlowNetwork {
+    pub fn new(nodes: usize) -> Self {
+        FlowNetwork {
+            adj: vec![Vec::new(); nodes],
+            to: Vec::new(),
+            cap: Vec::new(),
+        }
+    }
+
+    // undirected edges can carry their capacity either way
+    pub fn from_graph<W: Clone, F: Fn(&W) -> i64>(graph: &Graph<W>, capacity: F) -> Self {
+        let mut network = FlowNetwork::new(graph.len());
+        for (a, b, w) in graph.edges() {
+            let c = capacity(w);
+            match graph.is_directed() {
+                true => network.add_edge(a, b, c, 0),
+                false => network.add_edge(a, b, c, c),
+            };
+        }
+        network
+    }
+
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    // returns the index of the forward edge
+    pub fn add_edge(&mut self, from: usize, to: usize, cap: i64, reverse_cap: i64) -> usize {
+        let e = self.to.len();
+        self.to.extend([to, from]);
+        self.cap.extend([cap, reverse_cap]);
+        self.adj[from].push(e);
+        self.adj[to].push(e + 1);
+        e
+    }
+
+    pub fn residual(&self, edge: usize) -> i64 {
+        self.cap[edge]
+    }
+
+    // Dinic's algorithm, pushing blocking flows along shortest paths; the network is left
+    // with the residual capacities
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> i64 {
+        assert_ne!(source, sink, "source and sink should differ");
+        let mut flow = 0;
+        while let Some(level) = self.levels(source, sink) {
+            let mut next = vec![0; self.len()];
+            loop {
+                let pushed = self.augment(source, sink, i64::MAX, &level, &mut next);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+        flow
+    }
+
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut level = vec![usize::MAX; self.len()];
+        let mut queue = VecDeque::from([source]);
+        level[source] = 0;
+        while let Some(n) = queue.pop_front() {
+            for &e in &self.adj[n] {
+                let m = self.to[e];
+                if self.cap[e] > 0 && level[m] == usize::MAX {
+                    level[m] = level[n] + 1;
+                    queue.push_back(m);
+                }
+            }
+        }
+        (level[sink] != usize::MAX).then_some(level)
+    }
+
+    fn augment(
+        &mut self,
+        n: usize,
+        sink: usize,
+        limit: i64,
+        level: &[usize],
+        next: &mut [usize],
+    ) -> i64 {
+        if n == sink {
+            return limit;
+        }
+        while next[n] < self.adj[n].len() {
+            let e = self.adj[n][next[n]];
+            let m = self.to[e];
+            if self.cap[e] > 0 && level[m] == level[n] + 1 {
+                let pushed = self.augment(m, sink, limit.min(self.cap[e]), level, next);
+                if pushed > 0 {
+                    self.cap[e] -= pushed;
+                    self.cap[e ^ 1] += pushed;
+                    return pushed;
+                }
+            }
+            next[n] += 1;
+        }
+        0
+    }
+
+    // after `max_flow`, the nodes still reachable from the source, which form the source
+    // side of a minimum cut
+    pub fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![source];
+        seen[source] = true;
+        while let Some(n) = stack.pop() {
+            for &e in &self.adj[n] {
+                let m = self.to[e];
+                if self.cap[e] > 0 && !seen[m] {
+                    seen[m] = true;
+                    stack.push(m);
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub weight: i64,
+    // each edge from its lower end, as `Graph::edges` gives them
+    pub edges: Vec<(usize, usize)>,
+    pub parts: [Vec<usize>; 2],
+}
+
+impl Cut {
+    fn new<W: Clone>(graph: &Graph<W>, weight: i64, side: &[bool]) -> Self {
+        let edges = graph
+            .edges()
+            .filter(|&(a, b, _)| side[a] != side[b])
+            .map(|(a, b, _)| (a, b))
+            .collect();
+        let (inside, outside) = (0..graph.len()).partition(|&n| side[n]);
+        Cut {
+            weight,
+            edges,
+            parts: [inside, outside],
+        }
+    }
+}
+
+// Stoer-Wagner: each phase adds nodes in order of how strongly they're attached to those
+// already added, and the last one's attachment is the cut of the phase; merging the last
+// two nodes keeps that cut from coming up again. None with fewer than two nodes, or for a
+// directed graph, where cuts aren't symmetric and this doesn't apply
+pub fn global_min_cut<W: Clone, F: Fn(&W) -> i64>(graph: &Graph<W>, weight: F) -> Option<Cut> {
+    let n = graph.len();
+    if n < 2 || graph.is_directed() {
+        return None;
+    }
+    let mut adj = vec![FastHashMap::<usize, i64>::default(); n];
+    for (a, b, w) in graph.edges().filter(|&(a, b, _)| a != b) {
+        *adj[a].entry(b).or_default() += weight(w);
+        *adj[b].entry(a).or_default() += weight(w);
+    }
+    let mut members = (0..n).map(|i| vec![i]).collect::<Vec<_>>();
+    let mut active = (0..n).collect::<Vec<_>>();
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    while active.len() > 1 {
+        let (mut attached, mut added) = (vec![0; n], vec![false; n]);
+        let mut heap = BinaryHeap::from([(0, active[0])]);
+        let (mut order, mut cut) = (Vec::with_capacity(active.len()), 0);
+        while let Some((w, v)) = heap.pop() {
+            if added[v] || w != attached[v] {
+                continue;
+            }
+            (added[v], cut) = (true, w);
+            order.push(v);
+            for (&u, &c) in &adj[v] {
+                if !added[u] {
+                    attached[u] += c;
+                    heap.push((attached[u], u));
+                }
+            }
+        }
+        if order.len() < active.len() {
+            // disconnected, so the part reached is cut off for free
+            let side = order.iter().flat_map(|&v| members[v].iter().copied());
+            best = Some((0, side.collect()));
+            break;
+        }
+        let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+        if best.as_ref().is_none_or(|(w, _)| cut < *w) {
+            best = Some((cut, members[t].clone()));
+        }
+        for (u, c) in std::mem::take(&mut adj[t]) {
+            adj[u].remove(&t);
+            if u != s {
+                *adj[s].entry(u).or_default() += c;
+                *adj[u].entry(s).or_default() += c;
+            }
+        }
+        let merged = std::mem::take(&mut members[t]);
+        members[s].extend(merged);
+        active.retain(|&v| v != t);
+    }
+    let (weight, side) = best?;
+    let mut inside = vec![false; n];
+    side.into_iter().for_each(|v| inside[v] = true);
+    Some(Cut::new(graph, weight, &inside))
+}
+
+#[cfg(test)]
+mod test_flow {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_flow_examples() {
+        // the network from CLRS figure 26.1
+        let mut network = FlowNetwork::new(6);
+        for (a, b, c) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (2, 1, 4),
+            (1, 3, 12),
+            (3, 2, 9),
+            (2, 4, 14),
+            (4, 3, 7),
+            (3, 5, 20),
+            (4, 5, 4),
+        ] {
+            network.add_edge(a, b, c, 0);
+        }
+        assert_eq!(network.max_flow(0, 5), 23);
+        assert_eq!(
+            network.source_side(0),
+            [true, true, true, false, true, false]
+        );
+
+        // two triangles joined by one edge
+        let mut graph = Graph::undirected(6);
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.add_edge(a, b, 2);
+        }
+        let cut = global_min_cut(&graph, |&w| w).unwrap();
+        assert_eq!(cut.weight, 2);
+        assert_eq!(cut.edges, [(2, 3)]);
+        let mut parts = cut.parts.clone();
+        parts.sort();
+        assert_eq!(parts, [vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(global_min_cut(&Graph::<()>::undirected(1), |_| 1), None);
+        assert_eq!(global_min_cut(&Graph::<()>::directed(2), |_| 1), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_flow_min_cut(edges in prop::collection::vec((0..7usize, 0..7usize, 1..5i64), 0..20)) {
+            let mut graph = Graph::undirected(7);
+            edges.iter().for_each(|&(a, b, w)| graph.add_edge(a, b, w));
+            let cut = global_min_cut(&graph, |&w| w).unwrap();
+            // every split of the nodes into two non-empty parts
+            let brute = (1..(1 << 6))
+                .map(|mask: u32| {
+                    let side = |n: usize| n < 6 && mask & (1 << n) != 0;
+                    graph.edges().filter(|&(a, b, _)| side(a) != side(b)).map(|e| e.2).sum::<i64>()
+                })
+                .min()
+                .unwrap();
+            prop_assert_eq!(cut.weight, brute);
+            prop_assert!(cut.parts.iter().all(|p| !p.is_empty()));
+            prop_assert_eq!(cut.parts[0].len() + cut.parts[1].len(), 7);
+            let crossing = graph.edges().filter(|&(a, b, _)| cut.edges.contains(&(a, b)));
+            prop_assert_eq!(crossing.map(|e| e.2).sum::<i64>(), cut.weight);
+            // the global minimum is the smallest of the minimum s-t cuts from any one node
+            let flows = (1..7).map(|t| FlowNetwork::from_graph(&graph, |&w| w).max_flow(0, t));
+            prop_assert_eq!(flows.min().unwrap(), brute);
+        }
+    }
+}
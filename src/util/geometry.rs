@@ -0,0 +1,132 @@
+use num::integer::gcd;
+
+use super::{math, Dir4};
+
+// polygons are (row, col) vertices in order, with the last joined back to the first
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+// the corners reached by walking each (dir, length) step from the origin, or None if
+// the steps don't lead back to it or a corner overflows
+pub fn polygon_from_steps<I: IntoIterator<Item = (Dir4, i64)>>(
+    steps: I,
+) -> Option<Vec<(i64, i64)>> {
+    let mut pos = (0i64, 0i64);
+    let mut vertices = Vec::new();
+    for (dir, n) in steps {
+        vertices.push(pos);
+        let d = dir.delta();
+        pos = (
+            pos.0.checked_add((d.row as i64).checked_mul(n)?)?,
+            pos.1.checked_add((d.col as i64).checked_mul(n)?)?,
+        );
+    }
+    (pos == (0, 0)).then_some(vertices)
+}
+
+fn edges(vertices: &[(i64, i64)]) -> impl '_ + Iterator<Item = ((i64, i64), (i64, i64))> {
+    (0..vertices.len()).map(|i| (vertices[i], vertices[(i + 1) % vertices.len()]))
+}
+
+// the sums below are worked out in i128 and are None if they don't fit back in an i64
+
+// shoelace formula, doubled so it stays an integer
+pub fn twice_area(vertices: &[(i64, i64)]) -> Option<i64> {
+    let cross = |(a, b): ((i64, i64), (i64, i64))| {
+        (a.0 as i128 * b.1 as i128).checked_sub(b.0 as i128 * a.1 as i128)
+    };
+    let sum = edges(vertices).try_fold(0i128, |sum, e| sum.checked_add(cross(e)?))?;
+    sum.checked_abs()?.try_into().ok()
+}
+
+pub fn boundary_points(vertices: &[(i64, i64)]) -> Option<i64> {
+    let points = |(a, b): ((i64, i64), (i64, i64))| {
+        gcd(b.0 as i128 - a.0 as i128, b.1 as i128 - a.1 as i128)
+    };
+    math::checked_sum(edges(vertices).map(points))?
+        .try_into()
+        .ok()
+}
+
+// Pick's theorem: A = I + B / 2 - 1
+pub fn interior_points(vertices: &[(i64, i64)]) -> Option<i64> {
+    Some((twice_area(vertices)? - boundary_points(vertices)? + 2) / 2)
+}
+
+pub fn locate(vertices: &[(i64, i64)], p: (i64, i64)) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        let cross = (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+        let between = |x: i64, y: i64, z: i64| x.min(y) <= z && z <= x.max(y);
+        if cross == 0 && between(a.0, b.0, p.0) && between(a.1, b.1, p.1) {
+            return Location::Boundary;
+        }
+        // a ray towards increasing col crosses edges that straddle the row
+        if (a.0 > p.0) != (b.0 > p.0) && (cross < 0) == (b.0 > a.0) {
+            inside = !inside;
+        }
+    }
+    match inside {
+        true => Location::Inside,
+        false => Location::Outside,
+    }
+}
+
+#[cfg(test)]
+mod test_geometry {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_geometry_examples() {
+        let steps = [
+            (Dir4::Right, 4),
+            (Dir4::Down, 3),
+            (Dir4::Left, 4),
+            (Dir4::Up, 3),
+        ];
+        let square = polygon_from_steps(steps).unwrap();
+        assert_eq!(square, [(0, 0), (0, 4), (3, 4), (3, 0)]);
+        assert_eq!(twice_area(&square), Some(24));
+        assert_eq!(boundary_points(&square), Some(14));
+        assert_eq!(interior_points(&square), Some(6));
+        assert_eq!(locate(&square, (1, 1)), Location::Inside);
+        assert_eq!(locate(&square, (3, 2)), Location::Boundary);
+        assert_eq!(locate(&square, (1, 5)), Location::Outside);
+        assert_eq!(locate(&square, (0, -1)), Location::Outside);
+        assert_eq!(polygon_from_steps(steps.into_iter().skip(1)), None);
+        let far = [(0, 0), (0, i64::MAX), (i64::MAX, i64::MAX), (i64::MAX, 0)];
+        assert_eq!((twice_area(&far), boundary_points(&far)), (None, None));
+        let huge = [(Dir4::Right, i64::MAX), (Dir4::Right, 1)];
+        assert_eq!(polygon_from_steps(huge), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_geometry_pick(vs in prop::collection::vec((-8..8i64, -8..8i64), 3), reverse: bool) {
+            let mut vs = vs;
+            let (a, b, c) = (vs[0], vs[1], vs[2]);
+            prop_assume!((b.0 - a.0) * (c.1 - a.1) != (b.1 - a.1) * (c.0 - a.0));
+            if reverse {
+                vs.reverse();
+            }
+            let (mut inside, mut boundary) = (0, 0);
+            for r in -8..8 {
+                for c in -8..8 {
+                    match locate(&vs, (r, c)) {
+                        Location::Inside => inside += 1,
+                        Location::Boundary => boundary += 1,
+                        Location::Outside => (),
+                    }
+                }
+            }
+            prop_assert_eq!(boundary_points(&vs), Some(boundary));
+            prop_assert_eq!(interior_points(&vs), Some(inside));
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::Ignore;
+
+// names numbered densely in order of first appearance
+#[derive(Debug, Clone, Default)]
+pub struct Interner<'a> {
+    names: Vec<&'a str>,
+    index: HashMap<&'a str, usize>,
+}
+
+impl<'a> Interner<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &'a str) -> usize {
+        *self.index.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.names.len() - 1
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    pub fn name(&self, node: usize) -> &'a str {
+        self.names[node]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+// adjacency lists of (node, weight); undirected edges are stored at both ends
+#[derive(Debug, Clone)]
+pub struct Graph<W = ()> {
+    adj: Vec<Vec<(usize, W)>>,
+    directed: bool,
+}
+
+impl<W: Clone> Graph<W> {
+    pub fn directed(nodes: usize) -> Self {
+        Graph {
+            adj: vec![Vec::new(); nodes],
+            directed: true,
+        }
+    }
+
+    pub fn undirected(nodes: usize) -> Self {
+        Graph {
+            adj: vec![Vec::new(); nodes],
+            directed: false,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn len(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adj.is_empty()
+    }
+
+    pub fn add_node(&mut self) -> usize {
+        self.adj.push(Vec::new());
+        self.adj.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        let nodes = from.max(to) + 1;
+        if self.adj.len() < nodes {
+            self.adj.resize(nodes, Vec::new());
+        }
+        if !self.directed && from != to {
+            self.adj[to].push((from, weight.clone()));
+        }
+        self.adj[from].push((to, weight));
+    }
+
+    pub fn neighbours(&self, node: usize) -> &[(usize, W)] {
+        &self.adj[node]
+    }
+
+    // each undirected edge once, from its lower end
+    pub fn edges(&self) -> impl '_ + Iterator<Item = (usize, usize, &W)> {
+        self.adj.iter().enumerate().flat_map(move |(a, es)| {
+            es.iter()
+                .filter(move |&&(b, _)| self.directed || a <= b)
+                .map(move |(b, w)| (a, *b, w))
+        })
+    }
+
+    pub fn reversed(&self) -> Self {
+        let mut rev = Graph {
+            adj: vec![Vec::new(); self.len()],
+            directed: self.directed,
+        };
+        for (a, b, w) in self.edges() {
+            rev.add_edge(b, a, w.clone());
+        }
+        rev
+    }
+
+    // weakly connected components for directed graphs
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut sets = UnionFind::new(self.len());
+        self.edges().for_each(|(a, b, _)| sets.union(a, b).ignore());
+        let mut groups = HashMap::<usize, Vec<usize>>::new();
+        let mut order = Vec::new();
+        for node in 0..self.len() {
+            let root = sets.find(node);
+            if !groups.contains_key(&root) {
+                order.push(root);
+            }
+            groups.entry(root).or_default().push(node);
+        }
+        order
+            .into_iter()
+            .map(|root| groups.remove(&root).unwrap())
+            .collect()
+    }
+
+    // Kahn's algorithm, None if there's a cycle
+    pub fn toposort(&self) -> Option<Vec<usize>> {
+        let mut indegree = vec![0; self.len()];
+        self.edges().for_each(|(_, b, _)| indegree[b] += 1);
+        let mut queue = (0..self.len())
+            .filter(|&n| indegree[n] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(n) = queue.pop_front() {
+            order.push(n);
+            for &(m, _) in &self.adj[n] {
+                indegree[m] -= 1;
+                if indegree[m] == 0 {
+                    queue.push_back(m);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    // Tarjan's algorithm without recursion; components come out in reverse topological order
+    pub fn strongly_connected(&self) -> Vec<Vec<usize>> {
+        let n = self.len();
+        let (mut index, mut low) = (vec![usize::MAX; n], vec![0; n]);
+        let (mut on_stack, mut stack, mut sccs) = (vec![false; n], Vec::new(), Vec::new());
+        let mut next = 0;
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            let mut calls = vec![(root, 0)];
+            while let Some((v, i)) = calls.pop() {
+                if i == 0 {
+                    (index[v], low[v]) = (next, next);
+                    next += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                } else {
+                    let w = self.adj[v][i - 1].0;
+                    low[v] = low[v].min(low[w]);
+                }
+                let unvisited = self.adj[v][i..]
+                    .iter()
+                    .position(|&(w, _)| {
+                        if on_stack[w] {
+                            low[v] = low[v].min(index[w]);
+                        }
+                        index[w] == usize::MAX
+                    })
+                    .map(|j| i + j);
+                if let Some(j) = unvisited {
+                    calls.push((v, j + 1));
+                    calls.push((self.adj[v][j].0, 0));
+                } else if low[v] == index[v] {
+                    let at = stack.iter().rposition(|&w| w == v).unwrap();
+                    let scc = stack.split_off(at);
+                    scc.iter().for_each(|&w| on_stack[w] = false);
+                    sccs.push(scc);
+                }
+            }
+        }
+        sccs
+    }
+}
+
+// builds a graph from named nodes
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<'a, W = ()> {
+    names: Interner<'a>,
+    graph: Graph<W>,
+}
+
+impl<'a, W: Clone> GraphBuilder<'a, W> {
+    pub fn directed() -> Self {
+        GraphBuilder {
+            names: Interner::new(),
+            graph: Graph::directed(0),
+        }
+    }
+
+    pub fn undirected() -> Self {
+        GraphBuilder {
+            names: Interner::new(),
+            graph: Graph::undirected(0),
+        }
+    }
+
+    pub fn node(&mut self, name: &'a str) -> usize {
+        let node = self.names.intern(name);
+        if node == self.graph.len() {
+            self.graph.add_node();
+        }
+        node
+    }
+
+    pub fn edge(&mut self, from: &'a str, to: &'a str, weight: W) -> (usize, usize) {
+        let (a, b) = (self.node(from), self.node(to));
+        self.graph.add_edge(a, b, weight);
+        (a, b)
+    }
+
+    pub fn build(self) -> (Graph<W>, Interner<'a>) {
+        (self.graph, self.names)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            (x, self.parent[x]) = (self.parent[x], root);
+        }
+        root
+    }
+
+    // false if they were already joined
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+}
+
+#[cfg(test)]
+mod test_graph {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_graph_examples() {
+        let mut builder = GraphBuilder::directed();
+        for (a, b) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("e", "d")] {
+            builder.edge(a, b, ());
+        }
+        let (graph, names) = builder.build();
+        assert_eq!(
+            (graph.len(), names.get("d"), names.name(4)),
+            (5, Some(3), "e")
+        );
+        assert_eq!(graph.toposort(), None);
+        assert_eq!(graph.components(), [vec![0, 1, 2, 3, 4]]);
+        let sccs = graph.strongly_connected();
+        assert_eq!(sccs, [vec![3], vec![0, 1, 2], vec![4]]);
+        let mut dag = Graph::directed(3);
+        dag.add_edge(2, 0, ());
+        dag.add_edge(0, 1, ());
+        assert_eq!(dag.toposort(), Some(vec![2, 0, 1]));
+        assert_eq!(dag.reversed().toposort(), Some(vec![1, 0, 2]));
+
+        let mut graph = Graph::undirected(5);
+        graph.add_edge(0, 1, 7);
+        graph.add_edge(3, 2, 1);
+        assert_eq!(graph.neighbours(2), [(3, 1)]);
+        assert_eq!(graph.edges().count(), 2);
+        assert_eq!(graph.components(), [vec![0, 1], vec![2, 3], vec![4]]);
+
+        let mut sets = UnionFind::new(4);
+        assert!(sets.union(0, 2) && !sets.union(2, 0));
+        assert!(sets.same(0, 2) && !sets.same(0, 1));
+        assert_eq!(sets.size(2), 2);
+    }
+
+    proptest! {
+        #[test]
+        fn test_graph_orders(edges in prop::collection::vec((0..8usize, 0..8usize), 0..16)) {
+            let mut graph = Graph::directed(8);
+            edges.iter().for_each(|&(a, b)| graph.add_edge(a, b, ()));
+            // reachability as a transitive closure, by Floyd-Warshall
+            let mut reach = [[false; 8]; 8];
+            (0..8).for_each(|i| reach[i][i] = true);
+            edges.iter().for_each(|&(a, b)| reach[a][b] = true);
+            for k in 0..8 {
+                for i in 0..8 {
+                    for j in 0..8 {
+                        reach[i][j] |= reach[i][k] && reach[k][j];
+                    }
+                }
+            }
+            let sccs = graph.strongly_connected();
+            let mut scc_of = [0; 8];
+            for (c, scc) in sccs.iter().enumerate() {
+                scc.iter().for_each(|&n| scc_of[n] = c);
+            }
+            for i in 0..8 {
+                for j in 0..8 {
+                    prop_assert_eq!(scc_of[i] == scc_of[j], reach[i][j] && reach[j][i]);
+                    // sinks first
+                    prop_assert!(!reach[i][j] || scc_of[i] >= scc_of[j]);
+                }
+            }
+            let acyclic = sccs.len() == 8 && edges.iter().all(|(a, b)| a != b);
+            match graph.toposort() {
+                Some(order) => {
+                    prop_assert!(acyclic);
+                    let mut at = [0; 8];
+                    order.iter().enumerate().for_each(|(i, &n)| at[n] = i);
+                    prop_assert!(edges.iter().all(|&(a, b)| at[a] < at[b]));
+                }
+                None => prop_assert!(!acyclic),
+            }
+        }
+    }
+}
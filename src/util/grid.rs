@@ -0,0 +1,224 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::{Index, IndexMut},
+};
+
+use crate::day::ParseError;
+
+use super::LineGrid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid size mismatch");
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        let cells = (0..height)
+            .flat_map(|r| (0..width).map(move |c| (r, c)))
+            .map(|(r, c)| f(r, c))
+            .collect();
+        Self::from_vec(width, height, cells)
+    }
+
+    pub fn parse<F>(input: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, ParseError>,
+    {
+        let (mut cells, mut width, mut height) = (Vec::new(), None, 0);
+        for line in input.trim().lines() {
+            let len = cells.len();
+            for c in line.chars() {
+                cells.push(cell(c)?);
+            }
+            let line_width = cells.len() - len;
+            if *width.get_or_insert(line_width) != line_width {
+                return Err(ParseError::new(format!(
+                    "expected {} cells on line {} but found {}",
+                    width.unwrap(),
+                    height + 1,
+                    line_width
+                )));
+            }
+            height += 1;
+        }
+        Ok(Self::from_vec(width.unwrap_or(0), height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, row: i32, col: i32) -> bool {
+        let (w, h) = (self.width() as i32, self.height() as i32);
+        row >= 0 && col >= 0 && row < h && col < w
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    pub fn try_get(&self, row: i32, col: i32) -> Option<&T> {
+        let (r, c) = (row as usize, col as usize);
+        self.in_bounds(row, col).then(|| &self[(r, c)])
+    }
+
+    // None only for an empty grid, which has nothing to wrap onto
+    pub fn get_wrapping(&self, row: i64, col: i64) -> Option<&T> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let r = row.rem_euclid(self.height as i64) as usize;
+        let c = col.rem_euclid(self.width as i64) as usize;
+        Some(&self[(r, c)])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl '_ + Iterator<Item = &[T]> {
+        (0..self.height).map(|r| self.row(r))
+    }
+
+    pub fn iter_col(&self, col: usize) -> impl '_ + Iterator<Item = &T> {
+        self.cells[col..].iter().step_by(self.width.max(1))
+    }
+
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (usize, usize, &T)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, x)| (i / width, i % width, x))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self::from_vec(width, height, vec![value; width * height])
+    }
+
+    pub fn transposed(&self) -> Self {
+        Self::from_fn(self.height, self.width, |r, c| self[(c, r)].clone())
+    }
+
+    pub fn rotated_cw(&self) -> Self {
+        let h = self.height;
+        Self::from_fn(self.height, self.width, |r, c| self[(h - 1 - c, r)].clone())
+    }
+
+    pub fn rotated_ccw(&self) -> Self {
+        let w = self.width;
+        Self::from_fn(self.height, self.width, |r, c| self[(c, w - 1 - r)].clone())
+    }
+
+    pub fn flipped_vertical(&self) -> Self {
+        let h = self.height;
+        Self::from_fn(self.width, self.height, |r, c| self[(h - 1 - r, c)].clone())
+    }
+
+    pub fn flipped_horizontal(&self) -> Self {
+        let w = self.width;
+        Self::from_fn(self.width, self.height, |r, c| self[(r, w - 1 - c)].clone())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(col < self.width, "column {} out of bounds", col);
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(col < self.width, "column {} out of bounds", col);
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl<'a> From<&LineGrid<'a>> for Grid<char> {
+    fn from(grid: &LineGrid<'a>) -> Self {
+        Self::from_fn(grid.width(), grid.height(), |r, c| grid.get(r, c).unwrap())
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (r, row) in self.rows().enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            row.iter().try_for_each(|x| write!(f, "{}", x))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_grid {
+    use super::*;
+    use crate::day::Expected;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {"
+        abc
+        def
+    "};
+
+    #[test]
+    fn test_grid_transforms() {
+        let grid = Grid::parse(EXAMPLE, Ok).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid, Grid::from(&LineGrid::new(EXAMPLE)));
+        assert_eq!(grid.to_string(), EXAMPLE.trim());
+        assert_eq!(grid.transposed().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotated_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotated_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flipped_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.flipped_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.rotated_cw().rotated_ccw(), grid);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.iter_col(2).collect::<String>(), "cf");
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.try_get(-1, 0), None);
+        assert_eq!(grid.get_wrapping(-1, 4), Some(&'e'));
+        assert_eq!(Grid::parse("", Ok).unwrap().get_wrapping(0, 0), None);
+        assert_eq!(grid.map(|&c| c as u8 - b'a')[(1, 2)], 5);
+        assert_eq!(
+            Grid::parse("ab\nc", Ok).unwrap_err(),
+            ParseError::new("expected 2 cells on line 2 but found 1")
+        );
+        assert!(Grid::parse("12\n3x", |c| c.to_digit(10).expected("a digit")).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+// the multiply and rotate hash used by rustc and Firefox, which is much quicker than
+// SipHash on small integer keys but no defence against inputs chosen to collide
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastHasher {
+    hash: u64,
+}
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FastHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.add(n as u64);
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.add(n as u64);
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.add(n as u64);
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.add(n);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.add(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FastBuildHasher = BuildHasherDefault<FastHasher>;
+// make these with `default()`, as `new()` is only for the standard hasher
+pub type FastHashMap<K, V> = HashMap<K, V, FastBuildHasher>;
+pub type FastHashSet<T> = HashSet<T, FastBuildHasher>;
+
+#[cfg(test)]
+mod test_hash {
+    use super::*;
+    use std::hash::{BuildHasher, Hash};
+
+    fn hash<T: Hash>(x: T) -> u64 {
+        FastBuildHasher::default().hash_one(x)
+    }
+
+    #[test]
+    fn test_hash_examples() {
+        assert_eq!(hash((1, 2)), hash((1, 2)));
+        assert_ne!(hash((1, 2)), hash((2, 1)));
+        assert_ne!(hash("abcdefghi"), hash("abcdefghj"));
+        let points = (0..100).flat_map(|r| (0..100).map(move |c| hash((r, c))));
+        assert_eq!(points.collect::<FastHashSet<_>>().len(), 10000);
+
+        let mut map = FastHashMap::default();
+        map.insert([1usize, 2, 3], 'a');
+        *map.entry([1, 2, 3]).or_default() = 'b';
+        assert_eq!(map.get(&[1, 2, 3]), Some(&'b'));
+    }
+}
@@ -0,0 +1,290 @@
+use num::PrimInt;
+
+// half-open: start is included and end is not
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    // None if `last` is the largest T, which no half-open interval can include
+    pub fn closed(first: T, last: T) -> Option<Self> {
+        Some(Self::new(first, last.checked_add(&T::one())?))
+    }
+
+    pub fn single(x: T) -> Option<Self> {
+        Self::closed(x, x)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        match self.is_empty() {
+            true => T::zero(),
+            false => self.end - self.start,
+        }
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // the parts below and from `at`, either of which may be empty
+    pub fn split_at(self, at: T) -> (Self, Self) {
+        let at = at.max(self.start).min(self.end.max(self.start));
+        (Self::new(self.start, at), Self::new(at, self.end))
+    }
+
+    // the parts up to and including `at`, and above it
+    pub fn split_after(self, at: T) -> (Self, Self) {
+        match at.checked_add(&T::one()) {
+            Some(next) => self.split_at(next),
+            None => (self, Self::new(self.end, self.end)),
+        }
+    }
+
+    // the parts below and above `other`, either of which may be empty
+    pub fn difference(self, other: Self) -> (Self, Self) {
+        if other.is_empty() {
+            return (self, Self::new(self.end, self.end));
+        }
+        let (below, rest) = self.split_at(other.start);
+        (below, rest.split_at(other.end).1)
+    }
+
+    // None if either end overflows
+    pub fn shift(self, by: T) -> Option<Self> {
+        Some(Self::new(
+            self.start.checked_add(&by)?,
+            self.end.checked_add(&by)?,
+        ))
+    }
+}
+
+// sorted, disjoint and with no two intervals touching
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    fn coalesce(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|i| !i.is_empty());
+        intervals.sort_unstable_by_key(|i| i.start);
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if last.end >= interval.start => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Interval<T>> {
+        self.intervals.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn len(&self) -> T {
+        self.iter().fold(T::zero(), |n, i| n + i.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= x);
+        self.intervals.get(i).is_some_and(|i| i.contains(x))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = Self::coalesce(intervals);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::coalesce(self.iter().chain(other.iter()).collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j, mut intervals) = (0, 0, Vec::new());
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let both = a.intersection(b);
+            if !both.is_empty() {
+                intervals.push(both);
+            }
+            match a.end < b.end {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        for mut a in self.iter() {
+            let (first, end) = (other.intervals.partition_point(|b| b.end <= a.start), a.end);
+            for &b in other.intervals[first..]
+                .iter()
+                .take_while(|b| b.start < end)
+            {
+                let (below, above) = a.difference(b);
+                if !below.is_empty() {
+                    intervals.push(below);
+                }
+                a = above;
+            }
+            if !a.is_empty() {
+                intervals.push(a);
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::coalesce(vec![interval])
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self::coalesce(iter.into_iter().collect())
+    }
+}
+
+// an axis-aligned box of N half-open intervals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IntervalBox<T, const N: usize> {
+    pub axes: [Interval<T>; N],
+}
+
+impl<T: PrimInt, const N: usize> IntervalBox<T, N> {
+    pub fn new(axes: [Interval<T>; N]) -> Self {
+        IntervalBox { axes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(Interval::is_empty)
+    }
+
+    pub fn volume(&self) -> T {
+        self.axes.iter().fold(T::one(), |v, i| v * i.len())
+    }
+
+    pub fn intersection(mut self, other: &Self) -> Self {
+        (0..N).for_each(|i| self.axes[i] = self.axes[i].intersection(other.axes[i]));
+        self
+    }
+
+    pub fn split_at(self, axis: usize, at: T) -> (Self, Self) {
+        let (below, above) = self.axes[axis].split_at(at);
+        let (mut lower, mut upper) = (self, self);
+        (lower.axes[axis], upper.axes[axis]) = (below, above);
+        (lower, upper)
+    }
+
+    pub fn split_after(self, axis: usize, at: T) -> (Self, Self) {
+        let (upto, above) = self.axes[axis].split_after(at);
+        let (mut lower, mut upper) = (self, self);
+        (lower.axes[axis], upper.axes[axis]) = (upto, above);
+        (lower, upper)
+    }
+}
+
+#[cfg(test)]
+mod test_interval {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_interval_ops() {
+        let a = Interval::closed(1, 4000).unwrap();
+        assert_eq!(a.len(), 4000);
+        assert_eq!(
+            a.split_at(1351),
+            (Interval::new(1, 1351), Interval::new(1351, 4001))
+        );
+        assert_eq!(a.split_at(0).0.len(), 0);
+        assert_eq!(a.split_after(1350), a.split_at(1351));
+        assert_eq!(Interval::closed(0, u8::MAX), None);
+        assert_eq!(Interval::new(1u8, 5).shift(251), None);
+        let b = Interval::new(250, u8::MAX);
+        assert_eq!(b.split_after(u8::MAX), (b, Interval::new(u8::MAX, u8::MAX)));
+        assert_eq!(
+            a.difference(Interval::new(10, 20)),
+            (Interval::new(1, 10), Interval::new(20, 4001))
+        );
+        let set = [
+            Interval::new(5, 8),
+            Interval::new(0, 2),
+            Interval::new(2, 3),
+        ]
+        .into_iter()
+        .collect::<IntervalSet<i32>>();
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [Interval::new(0, 3), Interval::new(5, 8)]
+        );
+        assert_eq!((set.len(), set.min()), (6, Some(0)));
+        assert!(set.contains(7) && !set.contains(3));
+        let cube = IntervalBox::new([Interval::<u64>::new(1, 4001); 4]);
+        let (lower, upper) = cube.split_at(2, 2006);
+        assert_eq!(cube.split_after(2, 2005), (lower, upper));
+        assert_eq!(lower.volume() + upper.volume(), cube.volume());
+        assert_eq!(lower.intersection(&upper).volume(), 0);
+    }
+
+    fn brute(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+        set.iter().flat_map(|i| i.start..i.end).collect()
+    }
+
+    fn intervals() -> impl Strategy<Value = IntervalSet<i32>> {
+        prop::collection::vec((-20..20, 0..8), 0..6).prop_map(|is| {
+            is.into_iter()
+                .map(|(s, l)| Interval::new(s, s + l))
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_interval_sets(a in intervals(), b in intervals(), x in -25..25) {
+            let (ba, bb) = (brute(&a), brute(&b));
+            prop_assert_eq!(brute(&a.union(&b)), &ba | &bb);
+            prop_assert_eq!(brute(&a.intersection(&b)), &ba & &bb);
+            prop_assert_eq!(brute(&a.difference(&b)), &ba - &bb);
+            prop_assert_eq!(a.len() as usize, ba.len());
+            prop_assert_eq!(a.contains(x), ba.contains(&x));
+            let d = a.difference(&b);
+            prop_assert!(d.intervals.windows(2).all(|w| w[0].end < w[1].start));
+        }
+    }
+}
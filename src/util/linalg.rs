@@ -0,0 +1,231 @@
+use std::ops::{Index, IndexMut};
+
+use num::{BigInt, BigRational, One, Zero};
+
+pub fn rational<T: Into<BigInt>>(x: T) -> BigRational {
+    BigRational::from_integer(x.into())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    entries: Vec<BigRational>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            entries: vec![BigRational::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut mat = Self::zeros(n, n);
+        (0..n).for_each(|i| mat[(i, i)] = BigRational::one());
+        mat
+    }
+
+    pub fn from_rows<T: Into<BigInt>>(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(rows.iter().all(|r| r.len() == cols), "ragged matrix");
+        Matrix {
+            rows: rows.len(),
+            cols,
+            entries: rows.into_iter().flatten().map(rational).collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, row: usize) -> &[BigRational] {
+        &self.entries[row * self.cols..(row + 1) * self.cols]
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        (0..self.cols).for_each(|c| self.entries.swap(a * self.cols + c, b * self.cols + c));
+    }
+
+    // subtracts f times row `src` from row `dst`
+    fn sub_row(&mut self, dst: usize, src: usize, f: &BigRational) {
+        for c in 0..self.cols {
+            let x = &self[(src, c)] * f;
+            self[(dst, c)] -= x;
+        }
+    }
+
+    // reduces to row echelon form in place, returning the pivot columns and whether
+    // an odd number of row swaps were made; full also clears above and scales pivots to 1
+    fn eliminate(&mut self, full: bool) -> (Vec<usize>, bool) {
+        let (mut pivots, mut odd) = (Vec::new(), false);
+        for c in 0..self.cols {
+            let r = pivots.len();
+            let Some(p) = (r..self.rows).find(|&i| !self[(i, c)].is_zero()) else {
+                continue;
+            };
+            if p != r {
+                self.swap_rows(p, r);
+                odd = !odd;
+            }
+            if full {
+                let inv = self[(r, c)].recip();
+                (c..self.cols).for_each(|j| self[(r, j)] *= &inv);
+            }
+            let below = r + 1..self.rows;
+            for i in (0..r).filter(|_| full).chain(below) {
+                if !self[(i, c)].is_zero() {
+                    let f = &self[(i, c)] / &self[(r, c)];
+                    self.sub_row(i, r, &f);
+                }
+            }
+            pivots.push(c);
+            if pivots.len() == self.rows {
+                break;
+            }
+        }
+        (pivots, odd)
+    }
+
+    // the reduced row echelon form and its pivot columns
+    pub fn rref(&self) -> (Matrix, Vec<usize>) {
+        let mut mat = self.clone();
+        let (pivots, _) = mat.eliminate(true);
+        (mat, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().eliminate(false).0.len()
+    }
+
+    pub fn determinant(&self) -> Option<BigRational> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let mut mat = self.clone();
+        let (pivots, odd) = mat.eliminate(false);
+        if pivots.len() < self.rows {
+            return Some(BigRational::zero());
+        }
+        let det = (0..self.rows).fold(BigRational::one(), |d, i| d * &mat[(i, i)]);
+        Some(if odd { -det } else { det })
+    }
+
+    // a solution to self * x = b with free variables set to zero, or None if there is none
+    pub fn solve(&self, b: &[BigRational]) -> Option<Vec<BigRational>> {
+        assert_eq!(b.len(), self.rows, "right hand side has the wrong length");
+        let mut aug = Matrix::zeros(self.rows, self.cols + 1);
+        for r in 0..self.rows {
+            (0..self.cols).for_each(|c| aug[(r, c)] = self[(r, c)].clone());
+            aug[(r, self.cols)] = b[r].clone();
+        }
+        let (pivots, _) = aug.eliminate(true);
+        if pivots.last() == Some(&self.cols) {
+            return None;
+        }
+        let mut x = vec![BigRational::zero(); self.cols];
+        for (r, &c) in pivots.iter().enumerate() {
+            x[c] = aug[(r, self.cols)].clone();
+        }
+        Some(x)
+    }
+
+    pub fn null_space(&self) -> Vec<Vec<BigRational>> {
+        let (rref, pivots) = self.rref();
+        let free = (0..self.cols).filter(|c| !pivots.contains(c));
+        free.map(|f| {
+            let mut v = vec![BigRational::zero(); self.cols];
+            v[f] = BigRational::one();
+            for (r, &c) in pivots.iter().enumerate() {
+                v[c] = -&rref[(r, f)];
+            }
+            v
+        })
+        .collect()
+    }
+
+    pub fn mul_vec(&self, v: &[BigRational]) -> Vec<BigRational> {
+        assert_eq!(v.len(), self.cols, "vector has the wrong length");
+        (0..self.rows)
+            .map(|r| self.row(r).iter().zip(v).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+}
+
+impl<T: Into<BigInt>, const N: usize, const M: usize> From<[[T; N]; M]> for Matrix {
+    fn from(rows: [[T; N]; M]) -> Self {
+        Self::from_rows(rows.into_iter().map(Vec::from).collect())
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = BigRational;
+
+    fn index(&self, (row, col): (usize, usize)) -> &BigRational {
+        assert!(col < self.cols, "column {} out of bounds", col);
+        &self.entries[row * self.cols + col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut BigRational {
+        assert!(col < self.cols, "column {} out of bounds", col);
+        &mut self.entries[row * self.cols + col]
+    }
+}
+
+#[cfg(test)]
+mod test_linalg {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_linalg_examples() {
+        let mat = Matrix::from([[2, 1, -1], [-3, -1, 2], [-2, 1, 2]]);
+        assert_eq!(mat.determinant(), Some(rational(-1)));
+        let b = [8, -11, -3].map(rational);
+        assert_eq!(
+            mat.solve(&b),
+            Some(vec![rational(2), rational(3), rational(-1)])
+        );
+        let singular = Matrix::from([[1, 2, 3], [2, 4, 6], [1, 0, 1]]);
+        assert_eq!(
+            (singular.rank(), singular.determinant()),
+            (2, Some(rational(0)))
+        );
+        assert_eq!(singular.solve(&[1, 1, 1].map(rational)), None);
+        let null = singular.null_space();
+        assert_eq!(null.len(), 1);
+        assert!(singular.mul_vec(&null[0]).iter().all(Zero::is_zero));
+        assert_eq!(Matrix::identity(3).determinant(), Some(rational(1)));
+        let (rref, pivots) = singular.rref();
+        assert_eq!(pivots, [0, 1]);
+        assert_eq!(rref, Matrix::from([[1, 0, 1], [0, 1, 1], [0, 0, 0]]));
+    }
+
+    proptest! {
+        #[test]
+        fn test_linalg_solve(
+            rows in prop::collection::vec(prop::collection::vec(-4..5i64, 4), 1..5),
+            x in prop::collection::vec(-4..5i64, 4),
+        ) {
+            let mat = Matrix::from_rows(rows);
+            let x = x.into_iter().map(rational).collect::<Vec<_>>();
+            let b = mat.mul_vec(&x);
+            let y = mat.solve(&b).unwrap();
+            prop_assert_eq!(mat.mul_vec(&y), b);
+            let null = mat.null_space();
+            prop_assert_eq!(mat.rank() + null.len(), mat.cols());
+            for v in null {
+                prop_assert!(mat.mul_vec(&v).iter().all(Zero::is_zero));
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use num::{CheckedAdd, CheckedMul, One, Zero};
+
+pub fn checked_sum<T: CheckedAdd + Zero, I: IntoIterator<Item = T>>(iter: I) -> Option<T> {
+    iter.into_iter()
+        .try_fold(T::zero(), |a, b| a.checked_add(&b))
+}
+
+pub fn checked_product<T: CheckedMul + One, I: IntoIterator<Item = T>>(iter: I) -> Option<T> {
+    iter.into_iter()
+        .try_fold(T::one(), |a, b| a.checked_mul(&b))
+}
+
+// (g, x, y) with a * x + b * y = g = gcd(a, b)
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1, mut x0, mut x1, mut y0, mut y1) = (a, b, 1, 0, 0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    match r0 < 0 {
+        true => (-r0, -x0, -y0),
+        false => (r0, x0, y0),
+    }
+}
+
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+pub fn mod_pow(base: i64, mut exp: u64, m: i64) -> i64 {
+    let (mut base, mut result) = (base.rem_euclid(m) as i128, 1 % m as i128);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m as i128;
+        }
+        base = base * base % m as i128;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+// solves x = r (mod m) for every (r, m), giving (x, lcm of the moduli) with 0 <= x < lcm,
+// or None if the congruences are inconsistent or the lcm overflows
+pub fn crt<I: IntoIterator<Item = (i64, i64)>>(congruences: I) -> Option<(i64, i64)> {
+    congruences.into_iter().try_fold((0, 1), |(x, m), (r, n)| {
+        let (g, p, _) = ext_gcd(m, n);
+        let diff = r - x;
+        if diff % g != 0 {
+            return None;
+        }
+        let lcm = m.checked_mul(n / g)?;
+        // x + m * k where m * k = diff (mod n)
+        let k = (diff / g) as i128 * p as i128 % (n / g) as i128;
+        let x = (x as i128 + m as i128 * k).rem_euclid(lcm as i128);
+        Some((x as i64, lcm))
+    })
+}
+
+// whether the congruences have a common solution at all, which tells an inconsistent
+// system apart from one whose solution doesn't fit when `crt` gives None
+pub fn crt_solvable(congruences: &[(i64, i64)]) -> bool {
+    congruences.iter().enumerate().all(|(i, &(r, m))| {
+        congruences[..i].iter().all(|&(s, n)| {
+            let (g, _, _) = ext_gcd(m, n);
+            (r as i128 - s as i128) % g as i128 == 0
+        })
+    })
+}
+
+// the first time at or after every offset that each (offset, period) cycle hits together
+pub fn first_simultaneous<I: IntoIterator<Item = (i64, i64)>>(cycles: I) -> Option<i64> {
+    let cycles = cycles.into_iter().collect::<Vec<_>>();
+    let (x, lcm) = crt(cycles.iter().copied())?;
+    let start = cycles.iter().map(|c| c.0).max().unwrap_or(0);
+    match x < start {
+        true => x.checked_add((start - x + lcm - 1) / lcm * lcm),
+        false => Some(x),
+    }
+}
+
+#[cfg(test)]
+mod test_math {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_math_examples() {
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(4, 10), None);
+        assert_eq!(mod_pow(2, 62, 1_000_000_007), (1i64 << 62) % 1_000_000_007);
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert!(!crt_solvable(&[(1, 4), (2, 6)]));
+        assert_eq!(crt([(0, 1 << 40), (1, (1 << 40) - 1)]), None);
+        assert!(crt_solvable(&[(0, 1 << 40), (1, (1 << 40) - 1)]));
+        assert_eq!(first_simultaneous([(3, 3), (5, 5)]), Some(15));
+        assert_eq!(first_simultaneous([(7, 2), (0, 3)]), Some(9));
+        assert_eq!(checked_sum([u8::MAX - 1, 1]), Some(u8::MAX));
+        assert_eq!(checked_sum([u8::MAX, 1]), None);
+        assert_eq!(checked_product([1i64 << 32, 1 << 31]), None);
+        assert_eq!(checked_product::<i64, _>([]), Some(1));
+    }
+
+    proptest! {
+        #[test]
+        fn test_math_crt(cycles in prop::collection::vec((0..30i64, 1..12i64), 1..4)) {
+            let brute = (0..2000i64).find(|&t| {
+                cycles.iter().all(|&(offset, period)| t >= offset && (t - offset) % period == 0)
+            });
+            prop_assert_eq!(first_simultaneous(cycles.iter().copied()), brute);
+            prop_assert_eq!(crt(cycles.iter().copied()).is_some(), crt_solvable(&cycles));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::hash::Hash;
+
+use super::hash::FastHashMap;
+
+// a cache for recursive functions, which get the memo back to make their own calls;
+// keys should be cheap to build, e.g. suffix lengths rather than the slices themselves
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: FastHashMap<K, V>,
+    limit: Option<usize>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            cache: FastHashMap::default(),
+            limit: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // holds at most `limit` results, forgetting them all when full
+    pub fn with_limit(limit: usize) -> Self {
+        Memo {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    pub fn get_or_compute<F: FnOnce(&mut Self) -> V>(&mut self, key: K, compute: F) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = compute(self);
+        if self.limit.is_some_and(|limit| self.cache.len() >= limit) {
+            self.cache.clear();
+        }
+        if self.limit != Some(0) {
+            self.cache.insert(key, value.clone());
+        }
+        value
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test_memo {
+    use super::*;
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        memo.get_or_compute(n, |memo| match n {
+            0 | 1 => n,
+            _ => fib(n - 1, memo) + fib(n - 2, memo),
+        })
+    }
+
+    #[test]
+    fn test_memo_stats() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2880067194370816120);
+        assert_eq!((memo.misses(), memo.hits(), memo.len()), (91, 88, 91));
+        assert_eq!(fib(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.hits(), 89);
+
+        let mut limited = Memo::with_limit(10);
+        assert_eq!(fib(30, &mut limited), 832040);
+        assert!(limited.len() <= 10);
+        let mut uncached = Memo::with_limit(0);
+        assert_eq!(fib(15, &mut uncached), 610);
+        assert_eq!((uncached.hits(), uncached.len()), (0, 0));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{fmt::Display, num::ParseIntError, str::FromStr};
+
+use crate::day::{Expected, ParseError};
+
+// every run of digits, made negative by a '-' directly before it unless that follows a
+// letter or digit, so that ranges like "1-3" give positive integers
+pub fn ints<T: FromStr<Err = ParseIntError>>(
+    s: &str,
+) -> impl '_ + Iterator<Item = Result<T, ParseError>> {
+    let bs = s.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bs.len() && !bs[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bs.len() {
+            return None;
+        }
+        let signed = i > 0 && bs[i - 1] == b'-' && (i < 2 || !bs[i - 2].is_ascii_alphanumeric());
+        let start = if signed { i - 1 } else { i };
+        while i < bs.len() && bs[i].is_ascii_digit() {
+            i += 1;
+        }
+        Some(s[start..i].parse().map_err(ParseError::from))
+    })
+}
+
+pub fn ints_array<T: FromStr<Err = ParseIntError>, const N: usize>(
+    s: &str,
+) -> Result<[T; N], ParseError> {
+    let ns = ints(s).collect::<Result<Vec<_>, _>>()?;
+    let found = ns.len();
+    ns.try_into()
+        .map_err(|_| ParseError::new(format!("expected {} integers, found {}", N, found)))
+}
+
+// blocks of lines separated by one or more blank lines
+pub fn paragraphs(s: &str) -> impl '_ + Iterator<Item = &str> {
+    let (mut pars, mut start, mut offset) = (Vec::new(), None, 0);
+    for line in s.split_inclusive('\n') {
+        match (line.trim().is_empty(), start) {
+            (true, Some(st)) => {
+                pars.push(s[st..offset].trim_end());
+                start = None;
+            }
+            (false, None) => start = Some(offset),
+            _ => (),
+        }
+        offset += line.len();
+    }
+    if let Some(st) = start {
+        pars.push(s[st..].trim_end());
+    }
+    pars.into_iter()
+}
+
+// "key: a b, c" as the key and its whitespace or comma separated values
+pub fn key_values(line: &str) -> Result<(&str, impl '_ + Iterator<Item = &str>), ParseError> {
+    let (key, values) = line.split_once(':').expected("':' after key")?;
+    let values = values
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|v| !v.is_empty());
+    Ok((key.trim(), values))
+}
+
+// matches `input` against a pattern of literals and "{}" fields, returning each field
+// with its byte offset; a field extends up to the first occurrence of the literal after it,
+// or the last occurrence for the final field
+pub fn scan_fields<'a>(
+    input: &'a str,
+    pattern: &str,
+    count: usize,
+) -> Result<Vec<(&'a str, usize)>, ParseError> {
+    let literals = pattern.split("{}").collect::<Vec<_>>();
+    if literals.len() != count + 1 || literals[1..literals.len() - 1].contains(&"") {
+        return Err(ParseError::new(format!("bad scan pattern \"{}\"", pattern)));
+    }
+    let column = |at: usize| input[..at].chars().count() + 1;
+    let mut at = 0;
+    let mut fields = Vec::with_capacity(count);
+    for (i, literal) in literals.iter().enumerate() {
+        if i > 0 {
+            let found = match i == count {
+                true if literal.is_empty() => Some(input.len() - at),
+                true => input[at..].rfind(literal),
+                false => input[at..].find(literal),
+            };
+            let end = found.map(|j| at + j).ok_or_else(|| {
+                ParseError::new(format!(
+                    "expected \"{}\" after column {}",
+                    literal,
+                    column(at)
+                ))
+            })?;
+            fields.push((&input[at..end], at));
+            at = end;
+        }
+        if !input[at..].starts_with(literal) {
+            return Err(ParseError::new(format!(
+                "expected \"{}\" at column {}",
+                literal,
+                column(at)
+            )));
+        }
+        at += literal.len();
+    }
+    match at == input.len() {
+        true => Ok(fields),
+        false => Err(ParseError::new(format!(
+            "unexpected \"{}\" at column {}",
+            &input[at..],
+            column(at)
+        ))),
+    }
+}
+
+pub fn parse_field<T: FromStr>((field, at): (&str, usize), input: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    field.parse().map_err(|err| {
+        let column = input[..at].chars().count() + 1;
+        ParseError::new(format!(
+            "invalid \"{}\" at column {} ({})",
+            field, column, err
+        ))
+    })
+}
+
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:literal => $($ty:ty),+ $(,)?) => {{
+        let input: &str = $input;
+        let count = [$(stringify!($ty)),+].len();
+        $crate::util::parse::scan_fields(input, $pattern, count).and_then(|fields| {
+            let mut fields = fields.into_iter();
+            Ok(($($crate::util::parse::parse_field::<$ty>(fields.next().unwrap(), input)?,)+))
+        })
+    }};
+}
+
+#[cfg(test)]
+mod test_parse {
+    use super::*;
+
+    fn err<T>(result: Result<T, ParseError>) -> String {
+        result.err().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        let ns = ints::<i64>("x=-3, y=12..-40").collect::<Result<Vec<_>, _>>();
+        assert_eq!(ns, Ok(vec![-3, 12, -40]));
+        assert_eq!(ints_array::<u8, 2>("1-2"), Ok([1, 2]));
+        assert!(ints_array::<u8, 2>("1 2 3").is_err());
+        assert!(ints_array::<u8, 1>("-1").is_err());
+        let pars = paragraphs("\r\na\r\nb\r\n\r\n\r\nc\n").collect::<Vec<_>>();
+        assert_eq!(pars, ["a\r\nb", "c"]);
+        let (key, values) = key_values("Time:  7 15,30").unwrap();
+        assert_eq!(
+            (key, values.collect::<Vec<_>>()),
+            ("Time", vec!["7", "15", "30"])
+        );
+        assert_eq!(
+            scan!("1,0,1~1,2,10", "{},{},{}~{},{},{}" => u8, u8, u8, u8, u8, u16),
+            Ok((1, 0, 1, 1, 2, 10))
+        );
+        assert_eq!(
+            scan!("a<2006:qkq", "{}<{}:{}" => char, u32, String),
+            Ok(('a', 2006, "qkq".to_string()))
+        );
+        assert_eq!(
+            err(scan!("1,x~3", "{},{}~{}" => u8, u8, u8)),
+            "invalid \"x\" at column 3 (invalid digit found in string)"
+        );
+        assert_eq!(
+            err(scan!("1,2-3", "{},{}~{}" => u8, u8, u8)),
+            "expected \"~\" after column 3"
+        );
+        assert_eq!(
+            err(scan!("<1>!", "<{}>" => u8)),
+            "unexpected \"!\" at column 4"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: i32,
+    pub col: i32,
+}
+
+impl Point {
+    pub const fn new(row: i32, col: i32) -> Self {
+        Point { row, col }
+    }
+
+    pub fn manhattan(self, other: Point) -> i32 {
+        (self.row - other.row).abs() + (self.col - other.col).abs()
+    }
+
+    pub fn step(self, dir: Dir4, n: i32) -> Point {
+        self + dir.delta() * n
+    }
+
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Dir4::ALL.into_iter().map(move |d| self + d)
+    }
+
+    pub fn diagonals(self) -> impl Iterator<Item = Point> {
+        Dir8::DIAGONAL.into_iter().map(move |d| self + d)
+    }
+
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        Dir8::ALL.into_iter().map(move |d| self + d)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((row, col): (i32, i32)) -> Self {
+        Point::new(row, col)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Self {
+        Point::new(row as i32, col as i32)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, n: i32) -> Point {
+        Point::new(self.row * n, self.col * n)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.row, -self.col)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Add<Dir4> for Point {
+    type Output = Point;
+
+    fn add(self, dir: Dir4) -> Point {
+        self + dir.delta()
+    }
+}
+
+impl Add<Dir8> for Point {
+    type Output = Point;
+
+    fn add(self, dir: Dir8) -> Point {
+        self + dir.delta()
+    }
+}
+
+impl AddAssign<Dir4> for Point {
+    fn add_assign(&mut self, dir: Dir4) {
+        *self = *self + dir;
+    }
+}
+
+// rows grow downwards, so Up is a step to the previous row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' | '^' | 'N' => Some(Dir4::Up),
+            'R' | '>' | 'E' => Some(Dir4::Right),
+            'D' | 'v' | 'S' => Some(Dir4::Down),
+            'L' | '<' | 'W' => Some(Dir4::Left),
+            _ => None,
+        }
+    }
+
+    pub fn delta(self) -> Point {
+        match self {
+            Dir4::Up => Point::new(-1, 0),
+            Dir4::Right => Point::new(0, 1),
+            Dir4::Down => Point::new(1, 0),
+            Dir4::Left => Point::new(0, -1),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Dir4::Up | Dir4::Down)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::N,
+        Dir8::NE,
+        Dir8::E,
+        Dir8::SE,
+        Dir8::S,
+        Dir8::SW,
+        Dir8::W,
+        Dir8::NW,
+    ];
+    pub const DIAGONAL: [Dir8; 4] = [Dir8::NE, Dir8::SE, Dir8::SW, Dir8::NW];
+
+    // the diagonals have no single character, so only the four cardinal directions parse
+    pub fn from_char(c: char) -> Option<Self> {
+        Dir4::from_char(c).map(Dir8::from)
+    }
+
+    pub fn delta(self) -> Point {
+        match self {
+            Dir8::N => Point::new(-1, 0),
+            Dir8::NE => Point::new(-1, 1),
+            Dir8::E => Point::new(0, 1),
+            Dir8::SE => Point::new(1, 1),
+            Dir8::S => Point::new(1, 0),
+            Dir8::SW => Point::new(1, -1),
+            Dir8::W => Point::new(0, -1),
+            Dir8::NW => Point::new(-1, -1),
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Self::ALL[2 * dir as usize]
+    }
+}
+
+#[cfg(test)]
+mod test_point {
+    use super::*;
+
+    #[test]
+    fn test_point_directions() {
+        let p = Point::new(2, 3);
+        assert_eq!(p + Dir4::Up, Point::new(1, 3));
+        assert_eq!(p.step(Dir4::Left, 3), Point::new(2, 0));
+        assert_eq!(p.manhattan(Point::new(-1, 5)), 5);
+        assert_eq!(-(p - Point::new(1, 1)) * 2, Point::new(-2, -4));
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Left.turn_right(), Dir4::Up);
+        assert_eq!(Dir4::Right.reverse(), Dir4::Left);
+        assert_eq!(Dir8::NW.turn_right(), Dir8::N);
+        assert_eq!(Dir8::SE.reverse(), Dir8::NW);
+        assert_eq!(Dir8::from(Dir4::Down), Dir8::S);
+        for (s, dir) in ["URDL", "^>v<", "NESW"].iter().zip(Dir4::ALL) {
+            assert!(s.chars().map(Dir4::from_char).eq(Dir4::ALL.map(Some)));
+            assert_eq!(dir.delta() + dir.reverse().delta(), Point::default());
+        }
+        assert_eq!(Dir4::from_char('x'), None);
+        assert_eq!(Dir8::from_char('v'), Some(Dir8::S));
+        assert_eq!(Dir8::from_char('W'), Some(Dir8::W));
+        assert_eq!(Dir8::from_char('x'), None);
+        assert_eq!(p.neighbours4().count(), 4);
+        assert!(p.diagonals().all(|q| p.manhattan(q) == 2));
+        assert!(!p.neighbours8().any(|q| q == p));
+    }
+}
@@ -0,0 +1,98 @@
+use num::{BigInt, One, Zero};
+
+// a polynomial sampled at 0, 1, 2, ..., held as its forward differences at 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    diffs: Vec<BigInt>,
+}
+
+impl Polynomial {
+    fn differences<T: Clone + Into<BigInt>>(samples: &[T]) -> Vec<Vec<BigInt>> {
+        let mut rows = vec![samples.iter().cloned().map(Into::into).collect::<Vec<_>>()];
+        while rows.last().unwrap().len() > 1 {
+            let row = rows.last().unwrap();
+            rows.push(row.windows(2).map(|w| &w[1] - &w[0]).collect());
+        }
+        rows
+    }
+
+    // the lowest degree polynomial through every sample
+    pub fn through<T: Clone + Into<BigInt>>(samples: &[T]) -> Self {
+        let rows = Self::differences(samples);
+        let mut diffs = rows
+            .iter()
+            .filter_map(|r| r.first().cloned())
+            .collect::<Vec<_>>();
+        while diffs.last().is_some_and(Zero::is_zero) {
+            diffs.pop();
+        }
+        Polynomial { diffs }
+    }
+
+    // the polynomial through the samples if some row of differences vanishes, which
+    // confirms its degree rather than just fitting the points
+    pub fn fit<T: Clone + Into<BigInt>>(samples: &[T]) -> Option<Self> {
+        let rows = Self::differences(samples);
+        let degree = rows.iter().position(|r| r.iter().all(Zero::is_zero))?;
+        Some(Polynomial {
+            diffs: rows[..degree].iter().map(|r| r[0].clone()).collect(),
+        })
+    }
+
+    // 0 for constants, including zero
+    pub fn degree(&self) -> usize {
+        self.diffs.len().saturating_sub(1)
+    }
+
+    // Newton's forward formula, the sum of diff k times x choose k, which works for
+    // any integer x
+    pub fn eval<X: Into<BigInt>>(&self, x: X) -> BigInt {
+        let x = x.into();
+        let (mut choose, mut sum) = (BigInt::one(), BigInt::zero());
+        for (k, diff) in self.diffs.iter().enumerate() {
+            sum += diff * &choose;
+            choose = choose * (&x - k) / (k + 1);
+        }
+        sum
+    }
+
+    // None if the value doesn't fit in T
+    pub fn checked_eval<T: TryFrom<BigInt>, X: Into<BigInt>>(&self, x: X) -> Option<T> {
+        T::try_from(self.eval(x)).ok()
+    }
+}
+
+#[cfg(test)]
+mod test_poly {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_poly_examples() {
+        let squares = Polynomial::fit(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(squares.degree(), 2);
+        assert_eq!(squares.eval(-3), BigInt::from(9));
+        assert_eq!(squares.eval(1u64 << 40), BigInt::from(1u128 << 80));
+        assert_eq!(squares.checked_eval::<i64, _>(4_000_000_000i64), None);
+        assert_eq!(
+            squares.checked_eval::<i64, _>(3_000_000_000u32),
+            Some(9e18 as i64)
+        );
+        assert_eq!(Polynomial::fit(&[0, 1, 4]), None);
+        assert_eq!(Polynomial::through(&[0, 1, 4]), squares);
+        assert_eq!(Polynomial::through(&[0, 0]).degree(), 0);
+        assert_eq!(Polynomial::through::<i32>(&[]).eval(5), BigInt::zero());
+    }
+
+    proptest! {
+        #[test]
+        fn test_poly_extrapolate(coeffs in prop::collection::vec(-50..50i64, 1..6), x in -100..100i64) {
+            let p = |x: i64| coeffs.iter().rev().fold(BigInt::zero(), |acc, &c| acc * x + c);
+            let samples = (0..coeffs.len() as i64 + 1).map(p).collect::<Vec<_>>();
+            let poly = Polynomial::fit(&samples).unwrap();
+            prop_assert!(poly.degree() < coeffs.len());
+            prop_assert_eq!(poly.eval(x), p(x));
+            prop_assert_eq!(Polynomial::through(&samples[..coeffs.len()]).eval(x), p(x));
+        }
+    }
+}
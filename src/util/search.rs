@@ -0,0 +1,357 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+    iter,
+    ops::Add,
+};
+
+use super::hash::{FastHashMap, FastHashSet};
+
+pub struct Search<N, C> {
+    nodes: Vec<N>,
+    index: FastHashMap<N, usize>,
+    dist: Vec<C>,
+    prev: Vec<Option<usize>>,
+    goal: Option<usize>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new() -> Self {
+        Search {
+            nodes: Vec::new(),
+            index: FastHashMap::default(),
+            dist: Vec::new(),
+            prev: Vec::new(),
+            goal: None,
+        }
+    }
+
+    fn visit(&mut self, node: N, dist: C, prev: Option<usize>) -> usize {
+        let i = self.nodes.len();
+        self.index.insert(node.clone(), i);
+        self.nodes.push(node);
+        self.dist.push(dist);
+        self.prev.push(prev);
+        i
+    }
+
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.map(|i| &self.nodes[i])
+    }
+
+    pub fn cost(&self) -> Option<C> {
+        self.goal.map(|i| self.dist[i])
+    }
+
+    pub fn dist(&self, node: &N) -> Option<C> {
+        self.index.get(node).map(|&i| self.dist[i])
+    }
+
+    // nodes still queued when the search stopped only have upper bounds
+    pub fn visited(&self) -> impl '_ + Iterator<Item = (&N, C)> {
+        self.nodes.iter().zip(self.dist.iter().copied())
+    }
+
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut i = self.index.get(node).copied();
+        let mut path = Vec::new();
+        while let Some(j) = i {
+            path.push(self.nodes[j].clone());
+            i = self.prev[j];
+        }
+        path.reverse();
+        (!path.is_empty()).then_some(path)
+    }
+
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal()?)
+    }
+}
+
+pub fn bfs<N, I, S, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut goal: G,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let (mut search, mut queue) = (Search::new(), VecDeque::new());
+    for start in starts {
+        if !search.index.contains_key(&start) {
+            queue.push_back(search.visit(start, 0, None));
+        }
+    }
+    while let Some(i) = queue.pop_front() {
+        if goal(&search.nodes[i]) {
+            search.goal = Some(i);
+            break;
+        }
+        let dist = search.dist[i] + 1;
+        for next in successors(&search.nodes[i]) {
+            if !search.index.contains_key(&next) {
+                queue.push_back(search.visit(next, dist, Some(i)));
+            }
+        }
+    }
+    search
+}
+
+// lazily yields each node with its distance in order of distance
+pub fn bfs_iter<N, I, S>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+) -> impl Iterator<Item = (N, usize)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+{
+    let mut seen = FastHashSet::default();
+    let mut queue = starts
+        .into_iter()
+        .filter(|start| seen.insert(start.clone()))
+        .map(|start| (start, 0))
+        .collect::<VecDeque<_>>();
+    iter::from_fn(move || {
+        let (node, dist) = queue.pop_front()?;
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back((next, dist + 1));
+            }
+        }
+        Some((node, dist))
+    })
+}
+
+pub fn dijkstra<N, C, I, S, G>(
+    starts: impl IntoIterator<Item = N>,
+    successors: S,
+    goal: G,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    astar(starts, successors, |_| C::default(), goal)
+}
+
+pub fn astar<N, C, I, S, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut heuristic: H,
+    mut goal: G,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: FnMut(&N) -> I,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let (mut search, mut heap) = (Search::new(), BinaryHeap::new());
+    for start in starts {
+        if !search.index.contains_key(&start) {
+            let estimate = heuristic(&start);
+            let i = search.visit(start, C::default(), None);
+            heap.push(Reverse((estimate, C::default(), i)));
+        }
+    }
+    while let Some(Reverse((_, dist, i))) = heap.pop() {
+        if dist > search.dist[i] {
+            continue;
+        }
+        if goal(&search.nodes[i]) {
+            search.goal = Some(i);
+            break;
+        }
+        for (next, cost) in successors(&search.nodes[i]) {
+            let next_dist = dist + cost;
+            let j = match search.index.get(&next) {
+                Some(&j) if search.dist[j] <= next_dist => continue,
+                Some(&j) => {
+                    (search.dist[j], search.prev[j]) = (next_dist, Some(i));
+                    j
+                }
+                None => search.visit(next, next_dist, Some(i)),
+            };
+            let estimate = next_dist + heuristic(&search.nodes[j]);
+            heap.push(Reverse((estimate, next_dist, j)));
+        }
+    }
+    search
+}
+
+// dijkstra for nodes that `index` maps one to one onto 0..size, keeping the distances in a
+// flat table instead of hashing; gives just the goal reached and its cost
+pub fn dijkstra_dense<N, C, I, X, S, G>(
+    size: usize,
+    mut index: X,
+    starts: impl IntoIterator<Item = N>,
+    mut successors: S,
+    mut goal: G,
+) -> Option<(N, C)>
+where
+    N: Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    X: FnMut(&N) -> usize,
+    S: FnMut(&N) -> I,
+    G: FnMut(&N) -> bool,
+{
+    let (mut best, mut heap) = (vec![None; size], BinaryHeap::new());
+    for start in starts {
+        let i = index(&start);
+        if best[i].is_none() {
+            best[i] = Some((C::default(), start));
+            heap.push(Reverse((C::default(), i)));
+        }
+    }
+    while let Some(Reverse((dist, i))) = heap.pop() {
+        let node = match &best[i] {
+            Some((d, node)) if *d == dist => node.clone(),
+            _ => continue,
+        };
+        if goal(&node) {
+            return Some((node, dist));
+        }
+        for (next, cost) in successors(&node) {
+            let (j, next_dist) = (index(&next), dist + cost);
+            if best[j].as_ref().is_none_or(|(d, _)| next_dist < *d) {
+                best[j] = Some((next_dist, next));
+                heap.push(Reverse((next_dist, j)));
+            }
+        }
+    }
+    None
+}
+
+// meets in the middle by expanding whichever frontier is smaller a layer at a time
+pub fn bidirectional_bfs<N, I, J, S, P>(
+    start: N,
+    goal: N,
+    mut successors: S,
+    mut predecessors: P,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+    J: IntoIterator<Item = N>,
+    S: FnMut(&N) -> I,
+    P: FnMut(&N) -> J,
+{
+    if start == goal {
+        return Some(vec![start]);
+    }
+    let mut forward = FastHashMap::from_iter([(start.clone(), None)]);
+    let mut backward = FastHashMap::from_iter([(goal.clone(), None)]);
+    let (mut forward_layer, mut backward_layer) = (vec![start], vec![goal]);
+    let meeting = loop {
+        if forward_layer.is_empty() || backward_layer.is_empty() {
+            return None;
+        }
+        let forwards = forward_layer.len() <= backward_layer.len();
+        let (layer, seen, other) = match forwards {
+            true => (&mut forward_layer, &mut forward, &backward),
+            false => (&mut backward_layer, &mut backward, &forward),
+        };
+        let mut next_layer = Vec::new();
+        let mut meeting = None;
+        'expand: for node in layer.drain(..) {
+            let nexts = match forwards {
+                true => successors(&node).into_iter().collect::<Vec<_>>(),
+                false => predecessors(&node).into_iter().collect(),
+            };
+            for next in nexts {
+                if seen.contains_key(&next) {
+                    continue;
+                }
+                seen.insert(next.clone(), Some(node.clone()));
+                if other.contains_key(&next) {
+                    meeting = Some(next);
+                    break 'expand;
+                }
+                next_layer.push(next);
+            }
+        }
+        *layer = next_layer;
+        if let Some(meeting) = meeting {
+            break meeting;
+        }
+    };
+    let walk = |links: &FastHashMap<N, Option<N>>| {
+        iter::successors(Some(meeting.clone()), |n| links[n].clone()).collect::<Vec<_>>()
+    };
+    let mut path = walk(&forward);
+    path.reverse();
+    path.extend(walk(&backward).into_iter().skip(1));
+    Some(path)
+}
+
+#[cfg(test)]
+mod test_search {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_search_grid() {
+        // a 4x4 grid with a wall down column 1 except the bottom row
+        let open =
+            |(r, c): (i32, i32)| (0..4).contains(&r) && (0..4).contains(&c) && (c != 1 || r == 3);
+        let moves = |&(r, c): &(i32, i32)| {
+            [(r + 1, c), (r - 1, c), (r, c + 1), (r, c - 1)]
+                .into_iter()
+                .filter(move |&p| open(p))
+        };
+        let search = bfs([(0, 0)], moves, |&p| p == (0, 2));
+        assert_eq!(search.cost(), Some(8));
+        assert_eq!(search.path().unwrap().len(), 9);
+        assert_eq!(search.dist(&(3, 1)), Some(4));
+        let order = bfs_iter([(0, 0)], moves)
+            .map(|(_, d)| d)
+            .collect::<Vec<_>>();
+        assert!(order.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(order.len(), 13);
+        let weighted = |&p: &(i32, i32)| moves(&p).map(|q| (q, if q.1 == 1 { 5 } else { 1 }));
+        let manhattan = |&(r, c): &(i32, i32)| r.abs() + (c - 2).abs();
+        let search = dijkstra([(0, 0)], weighted, |&p| p == (0, 2));
+        assert_eq!(search.cost(), Some(12));
+        let index = |&(r, c): &(i32, i32)| (4 * r + c) as usize;
+        let dense = dijkstra_dense(16, index, [(0, 0)], weighted, |&p| p == (0, 2));
+        assert_eq!(dense, Some(((0, 2), 12)));
+        let search = astar([(0, 0)], weighted, manhattan, |&p| p == (0, 2));
+        assert_eq!(search.cost(), Some(12));
+        let path = bidirectional_bfs((0, 0), (0, 2), moves, moves).unwrap();
+        assert_eq!((path[0], path[8], path.len()), ((0, 0), (0, 2), 9));
+        assert_eq!(bidirectional_bfs((0, 0), (9, 9), moves, moves), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_search_agree(
+            edges in prop::collection::vec((0..12usize, 0..12usize), 0..30),
+            goal in 0..12usize,
+        ) {
+            let succ = |n: &usize| edges.iter().filter(|e| e.0 == *n).map(|e| e.1).collect::<Vec<_>>();
+            let pred = |n: &usize| edges.iter().filter(|e| e.1 == *n).map(|e| e.0).collect::<Vec<_>>();
+            let unit = |n: &usize| succ(n).into_iter().map(|m| (m, 1)).collect::<Vec<_>>();
+            let cost = bfs([0], succ, |&n| n == goal).cost();
+            prop_assert_eq!(dijkstra([0], unit, |&n| n == goal).cost(), cost);
+            let dense = dijkstra_dense(12, |&n| n, [0], unit, |&n| n == goal);
+            prop_assert_eq!(dense.map(|(_, c)| c), cost);
+            let path = bidirectional_bfs(0, goal, succ, pred);
+            prop_assert_eq!(path.as_ref().map(|p| p.len() - 1), cost);
+            if let Some(path) = path {
+                prop_assert!(path.windows(2).all(|w| edges.contains(&(w[0], w[1]))));
+            }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::ops::Range;
+
+use super::{hash::FastHashMap, Grid, Ignore, LineGrid, Point};
+
+// the rows and columns a grid can hold cells in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bounds {
+    pub rows: Range<i32>,
+    pub cols: Range<i32>,
+}
+
+impl Bounds {
+    pub fn width(&self) -> usize {
+        self.cols.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.rows.contains(&point.row) && self.cols.contains(&point.col)
+    }
+}
+
+fn grown(bounds: Option<Bounds>, point: Point) -> Bounds {
+    let (r, c) = (point.row, point.col);
+    match bounds {
+        Some(Bounds { rows, cols }) => Bounds {
+            rows: rows.start.min(r)..rows.end.max(r + 1),
+            cols: cols.start.min(c)..cols.end.max(c + 1),
+        },
+        None => Bounds {
+            rows: r..r + 1,
+            cols: c..c + 1,
+        },
+    }
+}
+
+// read access shared by the dense, tiled and sparse grids
+pub trait GridView {
+    type Cell;
+
+    fn at(&self, point: Point) -> Option<Self::Cell>;
+
+    // None if the grid goes on forever
+    fn bounds(&self) -> Option<Bounds>;
+
+    fn points(&self) -> impl Iterator<Item = Point> {
+        let Bounds { rows, cols } = self.bounds().expect("grid should be bounded");
+        rows.flat_map(move |r| cols.clone().map(move |c| Point::new(r, c)))
+    }
+}
+
+impl<G: GridView> GridView for &G {
+    type Cell = G::Cell;
+
+    fn at(&self, point: Point) -> Option<Self::Cell> {
+        (*self).at(point)
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        (*self).bounds()
+    }
+}
+
+impl<'a> GridView for LineGrid<'a> {
+    type Cell = char;
+
+    fn at(&self, point: Point) -> Option<char> {
+        self.get_point(point)
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let (w, h) = (self.width() as i32, self.height() as i32);
+        Some(Bounds {
+            rows: 0..h,
+            cols: 0..w,
+        })
+    }
+}
+
+impl<T: Clone> GridView for Grid<T> {
+    type Cell = T;
+
+    fn at(&self, point: Point) -> Option<T> {
+        self.try_get(point.row, point.col).cloned()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let (w, h) = (self.width() as i32, self.height() as i32);
+        Some(Bounds {
+            rows: 0..h,
+            cols: 0..w,
+        })
+    }
+}
+
+// a bounded grid repeated in every direction, so each cell wraps to the one its own
+// height or width away
+#[derive(Debug, Clone)]
+pub struct Tiled<G> {
+    grid: G,
+    tile: Bounds,
+}
+
+impl<G: GridView> Tiled<G> {
+    pub fn new(grid: G) -> Self {
+        let tile = grid.bounds().expect("can only tile a bounded grid");
+        assert!(
+            tile.width() > 0 && tile.height() > 0,
+            "can't tile an empty grid"
+        );
+        Tiled { grid, tile }
+    }
+
+    // which copy of the grid a point falls in, (0, 0) being the original
+    pub fn tile_of(&self, point: Point) -> (i32, i32) {
+        let (h, w) = (self.tile.height() as i32, self.tile.width() as i32);
+        (
+            (point.row - self.tile.rows.start).div_euclid(h),
+            (point.col - self.tile.cols.start).div_euclid(w),
+        )
+    }
+
+    pub fn wrap(&self, point: Point) -> Point {
+        let (h, w) = (self.tile.height() as i32, self.tile.width() as i32);
+        Point::new(
+            self.tile.rows.start + (point.row - self.tile.rows.start).rem_euclid(h),
+            self.tile.cols.start + (point.col - self.tile.cols.start).rem_euclid(w),
+        )
+    }
+}
+
+impl<G: GridView> GridView for Tiled<G> {
+    type Cell = G::Cell;
+
+    fn at(&self, point: Point) -> Option<Self::Cell> {
+        self.grid.at(self.wrap(point))
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+}
+
+// cells scattered over an unbounded plane, keeping the smallest box around them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: FastHashMap<Point, T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: FastHashMap::default(),
+            bounds: None,
+        }
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(grown(self.bounds.take(), point));
+        self.cells.insert(point, value)
+    }
+
+    // only rescans the cells when the point was on the edge of the box
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let removed = self.cells.remove(&point)?;
+        let Some(Bounds { rows, cols }) = &self.bounds else {
+            unreachable!()
+        };
+        let edges = [rows.start, rows.end - 1, cols.start, cols.end - 1];
+        if edges[..2].contains(&point.row) || edges[2..].contains(&point.col) {
+            self.bounds = self.cells.keys().fold(None, |b, &p| Some(grown(b, p)));
+        }
+        Some(removed)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&p, x)| (p, x))
+    }
+
+    // one line per row of the bounding box
+    pub fn render<F: Fn(Option<&T>) -> char>(&self, cell: F) -> String {
+        let Some(Bounds { rows, cols }) = &self.bounds else {
+            return String::new();
+        };
+        let lines = rows.clone().map(|r| {
+            cols.clone()
+                .map(|c| cell(self.get(Point::new(r, c))))
+                .collect::<String>()
+        });
+        lines.collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        iter.into_iter()
+            .for_each(|(p, x)| grid.insert(p, x).ignore());
+        grid
+    }
+}
+
+impl<T: Clone> GridView for SparseGrid<T> {
+    type Cell = T;
+
+    fn at(&self, point: Point) -> Option<T> {
+        self.get(point).cloned()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        // an empty sparse grid is bounded, just with nothing in it
+        Some(self.bounds.clone().unwrap_or(Bounds {
+            rows: 0..0,
+            cols: 0..0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test_view {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_view_tiled() {
+        let grid = LineGrid::new("ab\ncd\nef");
+        let tiled = Tiled::new(&grid);
+        assert_eq!(tiled.at(Point::new(-1, 0)), Some('e'));
+        assert_eq!(tiled.at(Point::new(3, -3)), Some('b'));
+        assert_eq!(tiled.tile_of(Point::new(-1, 4)), (-1, 2));
+        assert_eq!(tiled.bounds(), None);
+        assert_eq!(grid.points().filter_map(|p| grid.at(p)).count(), 6);
+        let digits = Grid::from_fn(3, 1, |_, c| c);
+        assert_eq!(Tiled::new(digits).at(Point::new(7, -1)), Some(2));
+    }
+
+    proptest! {
+        #[test]
+        fn test_view_sparse(ops in prop::collection::vec((-5..5i32, -5..5i32, any::<bool>()), 0..40)) {
+            let (mut sparse, mut map) = (SparseGrid::new(), HashMap::new());
+            for (r, c, add) in ops {
+                let p = Point::new(r, c);
+                match add {
+                    true => prop_assert_eq!(sparse.insert(p, r * c), map.insert(p, r * c)),
+                    false => prop_assert_eq!(sparse.remove(p), map.remove(&p)),
+                }
+            }
+            let bounds = sparse.bounds().unwrap();
+            prop_assert_eq!(sparse.len(), map.len());
+            prop_assert!(map.keys().all(|&p| bounds.contains(p)));
+            let rows = map.keys().map(|p| p.row).collect::<Vec<_>>();
+            let cols = map.keys().map(|p| p.col).collect::<Vec<_>>();
+            if !map.is_empty() {
+                prop_assert_eq!(bounds.rows.start, *rows.iter().min().unwrap());
+                prop_assert_eq!(bounds.cols.end - 1, *cols.iter().max().unwrap());
+            }
+            let rendered = sparse.render(|x| if x.is_some() { '#' } else { '.' });
+            prop_assert_eq!(rendered.matches('#').count(), map.len());
+            prop_assert_eq!(rendered.lines().count(), bounds.height());
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    day::{Day, Error},
+    util::LineGrid,
+};
+
+pub type Rgb = [u8; 3];
+
+const SCALE: usize = 4;
+const DELAY: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            pixels: vec![[0; 3]; width * height],
+        }
+    }
+
+    pub fn from_grid<F: Fn(char) -> Rgb>(grid: &LineGrid<'_>, color: F) -> Self {
+        let mut frame = Self::new(grid.width(), grid.height());
+        grid.iter()
+            .for_each(|(r, c, ch)| frame.set(r, c, color(ch)));
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<Rgb> {
+        (row < self.height && col < self.width).then(|| self.pixels[row * self.width + col])
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, color: Rgb) {
+        if row < self.height && col < self.width {
+            self.pixels[row * self.width + col] = color;
+        }
+    }
+
+    fn scaled_rgb(&self, scale: usize) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(3 * self.pixels.len() * scale * scale);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            for _ in 0..scale {
+                row.iter()
+                    .for_each(|px| (0..scale).for_each(|_| bytes.extend(px)));
+            }
+        }
+        bytes
+    }
+
+    fn scaled_size(&self, scale: usize) -> (usize, usize) {
+        (self.width * scale, self.height * scale)
+    }
+}
+
+pub trait Backend {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()>;
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    Terminal,
+    Ppm(PathBuf),
+    Png(PathBuf),
+    Gif(Option<PathBuf>),
+}
+
+impl Output {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (kind, path) = match spec.split_once('=') {
+            Some((kind, path)) => (kind, Some(PathBuf::from(path))),
+            None => (spec, None),
+        };
+        let dir = || path.clone().unwrap_or_else(|| PathBuf::from("cache"));
+        match kind {
+            "term" if path.is_none() => Some(Self::Terminal),
+            "ppm" => Some(Self::Ppm(dir())),
+            "png" => Some(Self::Png(dir())),
+            "gif" => Some(Self::Gif(path)),
+            _ => None,
+        }
+    }
+
+    pub fn backend(&self, day: usize) -> io::Result<Box<dyn Backend>> {
+        Ok(match self {
+            Self::Terminal => Box::new(Terminal::default()),
+            Self::Ppm(dir) => Box::new(Images::new(dir, day, ImageFormat::Ppm)?),
+            Self::Png(dir) => Box::new(Images::new(dir, day, ImageFormat::Png)?),
+            Self::Gif(path) => {
+                let default = || Path::new("cache").join(format!("day{:02}.gif", day));
+                Box::new(Gif::new(path.clone().unwrap_or_else(default)))
+            }
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Terminal {
+    drawn: bool,
+}
+
+impl Backend for Terminal {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let mut out = String::new();
+        if self.drawn {
+            thread::sleep(DELAY);
+            out.push_str("\x1b[H");
+        } else {
+            out.push_str("\x1b[2J\x1b[H");
+        }
+        // each character cell shows two rows using the upper half block
+        for r in (0..frame.height()).step_by(2) {
+            for c in 0..frame.width() {
+                let [r0, g0, b0] = frame.get(r, c).unwrap();
+                let [r1, g1, b1] = frame.get(r + 1, c).unwrap_or([0; 3]);
+                write!(
+                    out,
+                    "\x1b[38;2;{r0};{g0};{b0}m\x1b[48;2;{r1};{g1};{b1}m\u{2580}"
+                )
+                .unwrap();
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        self.drawn = true;
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()
+    }
+}
+
+enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+pub struct Images {
+    dir: PathBuf,
+    day: usize,
+    format: ImageFormat,
+    count: usize,
+}
+
+impl Images {
+    fn new(dir: &Path, day: usize, format: ImageFormat) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Images {
+            dir: dir.to_path_buf(),
+            day,
+            format,
+            count: 0,
+        })
+    }
+}
+
+impl Backend for Images {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let ext = match self.format {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        };
+        let name = format!("day{:02}_{:04}.{}", self.day, self.count, ext);
+        let mut file = BufWriter::new(File::create(self.dir.join(name))?);
+        self.count += 1;
+        match self.format {
+            ImageFormat::Ppm => write_ppm(&mut file, frame, SCALE)?,
+            ImageFormat::Png => write_png(&mut file, frame, SCALE)?,
+        }
+        file.flush()
+    }
+}
+
+fn write_ppm<W: Write>(w: &mut W, frame: &Frame, scale: usize) -> io::Result<()> {
+    let (width, height) = frame.scaled_size(scale);
+    write!(w, "P6\n{} {}\n255\n", width, height)?;
+    w.write_all(&frame.scaled_rgb(scale))
+}
+
+fn write_png<W: Write>(w: &mut W, frame: &Frame, scale: usize) -> io::Result<()> {
+    let (width, height) = frame.scaled_size(scale);
+    let mut encoder = png::Encoder::new(w, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer
+        .write_image_data(&frame.scaled_rgb(scale))
+        .map_err(io::Error::other)
+}
+
+pub struct Gif {
+    path: PathBuf,
+    encoder: Option<gif::Encoder<BufWriter<File>>>,
+}
+
+impl Gif {
+    fn new(path: PathBuf) -> Self {
+        Gif {
+            path,
+            encoder: None,
+        }
+    }
+}
+
+impl Backend for Gif {
+    fn frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let (width, height) = frame.scaled_size(SCALE);
+        // GIF sizes are 16 bits, so anything bigger can't be written at all
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}x{} frame is too big for a GIF", width, height),
+            ));
+        };
+        if self.encoder.is_none() {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let file = BufWriter::new(File::create(&self.path)?);
+            let mut encoder =
+                gif::Encoder::new(file, width, height, &[]).map_err(io::Error::other)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(io::Error::other)?;
+            self.encoder = Some(encoder);
+        }
+        let mut gif_frame = gif::Frame::from_rgb_speed(width, height, &frame.scaled_rgb(SCALE), 10);
+        gif_frame.delay = (DELAY.as_millis() / 10) as u16;
+        self.encoder
+            .as_mut()
+            .unwrap()
+            .write_frame(&gif_frame)
+            .map_err(io::Error::other)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.encoder
+            .take()
+            .map_or(Ok(()), |encoder| encoder.into_inner()?.flush())
+    }
+}
+
+pub trait Visualize<'a>: Day<'a> {
+    fn visualize(input: &Self::Input, backend: &mut dyn Backend) -> io::Result<()>;
+
+    fn parse_and_visualize(input: &'a str, output: &Output) -> Result<(), Error> {
+        let input = Self::parse(input).map_err(|err| Error::ParsingInput(Self::DAY, err))?;
+        let mut backend = output.backend(Self::DAY).map_err(Error::Visualizing)?;
+        Self::visualize(&input, backend.as_mut())
+            .and_then(|()| backend.finish())
+            .map_err(Error::Visualizing)
+    }
+}
+
+#[cfg(test)]
+mod test_viz {
+    use super::*;
+
+    #[test]
+    fn test_viz_ppm() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(0, 1, [1, 2, 3]);
+        frame.set(5, 5, [9, 9, 9]);
+        let mut ppm = Vec::new();
+        write_ppm(&mut ppm, &frame, 2).unwrap();
+        let mut expected = b"P6\n4 2\n255\n".to_vec();
+        let row = [[0, 0, 0], [0, 0, 0], [1, 2, 3], [1, 2, 3]].concat();
+        expected.extend(row.iter().chain(&row));
+        assert_eq!(ppm, expected);
+        assert_eq!(Output::parse("gif"), Some(Output::Gif(None)));
+        let mut gif = Gif::new(std::env::temp_dir().join("aoc2023_test_viz.gif"));
+        assert!(gif.frame(&Frame::new(1 << 16, 1)).is_err());
+        assert!(gif.encoder.is_none());
+        assert_eq!(Output::parse("png=out"), Some(Output::Png("out".into())));
+        assert_eq!(Output::parse("term=out"), None);
+    }
+}